@@ -0,0 +1,157 @@
+//! The single path for committing session state from the Ephemeral Rollup
+//! back to the base layer.
+//!
+//! Every commit instruction (checkpoint, commit, undelegate, settle) goes
+//! through `commit_to_base_layer`, so the SDK argument order lives in one place.
+
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::ephem::{commit_accounts, commit_and_undelegate_accounts};
+
+/// Schedule a commit of `accounts`, undelegating them as well when
+/// `undelegate` is set. The SDK takes (payer, accounts, magic context,
+/// magic program), in that order.
+pub fn commit_to_base_layer<'info>(
+    payer: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    magic_context: &AccountInfo<'info>,
+    magic_program: &AccountInfo<'info>,
+    undelegate: bool,
+) -> Result<()> {
+    let accounts = accounts.iter().collect();
+    if undelegate {
+        commit_and_undelegate_accounts(payer, accounts, magic_context, magic_program)?;
+    } else {
+        commit_accounts(payer, accounts, magic_context, magic_program)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{CommitSession, CommitSessionBumps, ErrorCode, GameSession, PlayerProfile};
+    use anchor_lang::solana_program::entrypoint::ProgramResult;
+    use anchor_lang::solana_program::instruction::Instruction;
+    use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+    use ephemeral_rollups_sdk::consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+    use std::sync::Mutex;
+
+    static INVOKED: Mutex<Vec<Instruction>> = Mutex::new(Vec::new());
+
+    // Records every CPI instead of executing it
+    struct RecordingStubs;
+
+    impl SyscallStubs for RecordingStubs {
+        fn sol_invoke_signed(
+            &self,
+            instruction: &Instruction,
+            _account_infos: &[AccountInfo],
+            _signers_seeds: &[&[&[u8]]],
+        ) -> ProgramResult {
+            INVOKED.lock().unwrap().push(instruction.clone());
+            Ok(())
+        }
+    }
+
+    // Tests run in parallel, so pick out the CPI that commits `account`
+    fn take_invoked(account: Pubkey) -> Instruction {
+        let mut invoked = INVOKED.lock().unwrap();
+        let index = invoked
+            .iter()
+            .position(|ix| ix.accounts.iter().any(|meta| meta.pubkey == account))
+            .unwrap();
+        invoked.remove(index)
+    }
+
+    fn commit(undelegate: bool) -> (Pubkey, Pubkey, Instruction) {
+        let payer_key = Pubkey::new_unique();
+        let session_key = Pubkey::new_unique();
+        let owner = crate::ID;
+        let (mut payer_lamports, mut session_lamports, mut ctx_lamports, mut prog_lamports) =
+            (0u64, 0u64, 0u64, 0u64);
+        let (mut payer_data, mut session_data, mut ctx_data, mut prog_data) =
+            (vec![], vec![0u8; 8], vec![], vec![]);
+
+        let payer = AccountInfo::new(&payer_key, true, true, &mut payer_lamports, &mut payer_data, &owner, false, 0);
+        let session = AccountInfo::new(&session_key, false, true, &mut session_lamports, &mut session_data, &owner, false, 0);
+        let magic_context = AccountInfo::new(&MAGIC_CONTEXT_ID, false, true, &mut ctx_lamports, &mut ctx_data, &owner, false, 0);
+        let magic_program = AccountInfo::new(&MAGIC_PROGRAM_ID, false, false, &mut prog_lamports, &mut prog_data, &owner, true, 0);
+
+        commit_to_base_layer(&payer, &[session], &magic_context, &magic_program, undelegate).unwrap();
+        let ix = take_invoked(session_key);
+        (payer_key, session_key, ix)
+    }
+
+    #[test]
+    fn commits_with_sdk_argument_order() {
+        set_syscall_stubs(Box::new(RecordingStubs));
+
+        for undelegate in [false, true] {
+            let (payer, session, ix) = commit(undelegate);
+            assert_eq!(ix.program_id, MAGIC_PROGRAM_ID);
+
+            let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+            assert_eq!(keys, vec![payer, MAGIC_CONTEXT_ID, session]);
+            assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+            assert!(ix.accounts[1].is_writable);
+
+            // Schedule-commit (1) vs schedule-commit-and-undelegate (2)
+            assert_eq!(ix.data[0], if undelegate { 2 } else { 1 });
+        }
+    }
+
+    // A session delegated with its profile, already ended by its last life
+    fn finish_accounts(player: Pubkey, player_profile: Option<Account<'static, PlayerProfile>>) -> CommitSession<'static> {
+        let mut session = session(player, false);
+        session.profile_delegated = true;
+        CommitSession {
+            session,
+            magic_context: account_info(MAGIC_CONTEXT_ID, false, crate::ID, vec![], false).clone(),
+            magic_program: account_info(MAGIC_PROGRAM_ID, false, crate::ID, vec![], true).clone(),
+            payer: signer(player),
+            authority: signer(player),
+            session_token: None,
+            player_profile,
+        }
+    }
+
+    #[test]
+    fn finishing_requires_the_delegated_profile() {
+        let mut accounts = finish_accounts(Pubkey::new_unique(), None);
+        let result = crate::fruitninja::undelegate_session(Context::new(
+            &crate::ID,
+            &mut accounts,
+            &[],
+            CommitSessionBumps::default(),
+        ));
+        assert_eq!(result.unwrap_err(), ErrorCode::DelegatedProfileRequired.into());
+    }
+
+    #[test]
+    fn finishing_undelegates_the_profile_too() {
+        set_syscall_stubs(Box::new(RecordingStubs));
+
+        let player = Pubkey::new_unique();
+        let player_profile = profile(player);
+        let profile_key = player_profile.key();
+        let mut accounts = finish_accounts(player, Some(player_profile));
+        let session_key = accounts.session.key();
+        crate::fruitninja::undelegate_session(Context::new(
+            &crate::ID,
+            &mut accounts,
+            &[],
+            CommitSessionBumps::default(),
+        ))
+        .unwrap();
+
+        let ix = take_invoked(session_key);
+        let keys: Vec<Pubkey> = ix.accounts.iter().skip(2).map(|meta| meta.pubkey).collect();
+        assert_eq!(keys, vec![session_key, profile_key]);
+        assert_eq!(ix.data[0], 2);
+
+        // Back on the base layer, the session no longer waits for its profile
+        let data = accounts.session.to_account_info().try_borrow_data().unwrap().to_vec();
+        assert!(!GameSession::try_deserialize(&mut data.as_slice()).unwrap().profile_delegated);
+    }
+}
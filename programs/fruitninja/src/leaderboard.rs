@@ -0,0 +1,169 @@
+//! Pure leaderboard ranking logic.
+//!
+//! Works on a plain `Vec<LeaderboardEntry>` so it can be unit tested without
+//! any account plumbing. The board is kept sorted best-first and holds at most
+//! one entry per player.
+
+use anchor_lang::prelude::*;
+use core::cmp::Ordering;
+
+// Leaderboard entry (serializable)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    pub player: Pubkey,  // 32
+    pub score: u64,      // 8
+    pub timestamp: i64,  // 8
+}
+
+/// Board order: higher score first, then earlier timestamp, then pubkey so
+/// the order is total and every node sorts the same way.
+pub fn rank_order(a: &LeaderboardEntry, b: &LeaderboardEntry) -> Ordering {
+    b.score
+        .cmp(&a.score)
+        .then(a.timestamp.cmp(&b.timestamp))
+        .then(a.player.cmp(&b.player))
+}
+
+/// Submit a score to a sorted board of at most `capacity` entries.
+///
+/// A player already on the board is only replaced when the new score is
+/// strictly higher. Returns `true` if the board changed.
+pub fn submit(entries: &mut Vec<LeaderboardEntry>, entry: LeaderboardEntry, capacity: usize) -> bool {
+    if capacity == 0 {
+        return false;
+    }
+
+    if let Some(pos) = rank_of(entries, &entry.player) {
+        if entry.score <= entries[pos].score {
+            return false;
+        }
+        // A higher score always ranks at or above the old slot, so the
+        // re-insert below cannot fall off the board.
+        entries.remove(pos);
+    }
+
+    let idx = entries.partition_point(|e| rank_order(e, &entry) == Ordering::Less);
+    if idx >= capacity {
+        return false;
+    }
+    entries.insert(idx, entry);
+    entries.truncate(capacity);
+    true
+}
+
+/// Take `player` off the board, returning their entry if they were on it.
+/// Everyone below moves up one rank.
+pub fn remove(entries: &mut Vec<LeaderboardEntry>, player: &Pubkey) -> Option<LeaderboardEntry> {
+    rank_of(entries, player).map(|pos| entries.remove(pos))
+}
+
+/// 0-indexed rank of `player`, if they are on the board.
+pub fn rank_of(entries: &[LeaderboardEntry], player: &Pubkey) -> Option<usize> {
+    entries.iter().position(|e| e.player == *player)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(player: &Pubkey, score: u64, timestamp: i64) -> LeaderboardEntry {
+        LeaderboardEntry {
+            player: *player,
+            score,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn keeps_one_entry_per_player() {
+        let alice = Pubkey::new_unique();
+        let mut board = Vec::new();
+
+        assert!(submit(&mut board, entry(&alice, 100, 1), 10));
+        assert!(submit(&mut board, entry(&alice, 200, 2), 10));
+
+        assert_eq!(board, vec![entry(&alice, 200, 2)]);
+    }
+
+    #[test]
+    fn ignores_scores_that_do_not_beat_existing_entry() {
+        let alice = Pubkey::new_unique();
+        let mut board = vec![entry(&alice, 200, 1)];
+
+        assert!(!submit(&mut board, entry(&alice, 150, 2), 10));
+        assert!(!submit(&mut board, entry(&alice, 200, 3), 10));
+
+        assert_eq!(board, vec![entry(&alice, 200, 1)]);
+    }
+
+    #[test]
+    fn replacement_moves_player_up() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let mut board = Vec::new();
+
+        submit(&mut board, entry(&bob, 300, 1), 10);
+        submit(&mut board, entry(&alice, 100, 2), 10);
+        submit(&mut board, entry(&alice, 400, 3), 10);
+
+        assert_eq!(rank_of(&board, &alice), Some(0));
+        assert_eq!(rank_of(&board, &bob), Some(1));
+        assert_eq!(board.len(), 2);
+    }
+
+    #[test]
+    fn equal_scores_rank_earlier_timestamp_first() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let mut board = Vec::new();
+
+        submit(&mut board, entry(&alice, 100, 20), 10);
+        submit(&mut board, entry(&bob, 100, 10), 10);
+
+        assert_eq!(rank_of(&board, &bob), Some(0));
+        assert_eq!(rank_of(&board, &alice), Some(1));
+    }
+
+    #[test]
+    fn respects_capacity() {
+        let players: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let mut board = Vec::new();
+
+        for (i, player) in players.iter().enumerate() {
+            submit(&mut board, entry(player, 100 * (i as u64 + 1), i as i64), 3);
+        }
+
+        assert_eq!(board.len(), 3);
+        assert_eq!(board[0].score, 400);
+        assert_eq!(rank_of(&board, &players[0]), None);
+
+        // A score below the last slot of a full board is rejected
+        let late = Pubkey::new_unique();
+        assert!(!submit(&mut board, entry(&late, 50, 9), 3));
+        assert_eq!(rank_of(&board, &late), None);
+    }
+
+    #[test]
+    fn remove_moves_lower_entries_up() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let carol = Pubkey::new_unique();
+        let mut board = Vec::new();
+        submit(&mut board, entry(&alice, 300, 1), 10);
+        submit(&mut board, entry(&bob, 200, 2), 10);
+        submit(&mut board, entry(&carol, 100, 3), 10);
+
+        assert_eq!(remove(&mut board, &alice), Some(entry(&alice, 300, 1)));
+        assert_eq!(rank_of(&board, &bob), Some(0));
+        assert_eq!(rank_of(&board, &carol), Some(1));
+        assert_eq!(remove(&mut board, &alice), None);
+        assert_eq!(board.len(), 2);
+    }
+
+    #[test]
+    fn zero_capacity_board_stays_empty() {
+        let mut board = Vec::new();
+        assert!(!submit(&mut board, entry(&Pubkey::new_unique(), 100, 1), 0));
+        assert!(board.is_empty());
+    }
+}
@@ -1,7 +1,12 @@
 #![allow(unexpected_cfgs)]
 #![allow(deprecated)]
 use anchor_lang::prelude::*;
+#[cfg(feature = "restrict-init")]
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
 use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_lang::system_program;
 
 // Ephemeral Rollups SDK imports (assumed available)
 use ephemeral_rollups_sdk::anchor::{
@@ -9,10 +14,23 @@ use ephemeral_rollups_sdk::anchor::{
     ephemeral,
 };
 use ephemeral_rollups_sdk::cpi::DelegateConfig;
-use ephemeral_rollups_sdk::ephem::{
-    commit_accounts,
-    commit_and_undelegate_accounts,
-};
+
+pub mod commit;
+pub mod leaderboard;
+pub mod migration;
+pub mod pause;
+pub mod replay;
+pub mod scoring;
+pub mod spawn;
+#[cfg(test)]
+mod test_utils;
+
+use commit::commit_to_base_layer;
+pub use leaderboard::LeaderboardEntry;
+use migration::{LegacyGameConfig, LegacyGameSession, LegacyPlayerProfile, LEGACY_LEADERBOARD_CAPACITY};
+use pause::require_not_paused;
+pub use replay::ReplayAction;
+pub use scoring::{ScoringCurve, ScoringTier};
 
 declare_id!("JCFR4CoTiRnTpL76ySSDCaHjJ2JtbME4wL3G5XpFnsgX");
 
@@ -23,38 +41,70 @@ pub mod fruitninja {
 
     /// Initialize a new game session for a player
     pub fn initialize_session(ctx: Context<InitializeSession>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+        require_not_banned(&ctx.accounts.player_ban)?;
         let session = &mut ctx.accounts.session;
         let clock = Clock::get()?;
         
+        session.version = GameSession::VERSION;
         session.player = ctx.accounts.player.key();
         session.bump = ctx.bumps.session;
+        session.game_index = 0;
         // Read defaults from config
         let config = &ctx.accounts.config;
-        session.current_score = 0;
-        session.combo = 0;
-        session.lives = config.max_lives;
-        session.is_active = true;
-        session.started_at = clock.unix_timestamp;
-        session.ended_at = None;
-        session.fruits_sliced = 0;
-        session.max_combo = 0;
+        let slot_hash = most_recent_slot_hash(&ctx.accounts.slot_hashes)?;
+        let seed = spawn::game_seed(&slot_hash, session.player.as_ref(), session.game_index);
+        session.reset_for_new_game(config.max_lives, clock.unix_timestamp, seed);
 
         msg!("Session initialized for player: {}", session.player);
 
             // Emit session started event
             emit!(SessionStarted {
                 player: session.player,
+                game_index: session.game_index,
+                rng_seed: session.rng_seed,
                 timestamp: session.started_at,
             });
     
             Ok(())
         }
 
+    /// Start a new game on an existing session once the previous one has
+    /// ended and its result has been recorded by end_session
+    pub fn start_new_game(ctx: Context<StartNewGame>) -> Result<()> {
+        let session = &mut ctx.accounts.session;
+        let config = &ctx.accounts.config;
+        require_not_paused(config)?;
+        require_not_banned(&ctx.accounts.player_ban)?;
+        let clock = Clock::get()?;
+
+        require!(!session.is_active, ErrorCode::SessionStillActive);
+        require!(session.finalized, ErrorCode::SessionNotFinalized);
+        require!(!session.leaderboard_pending, ErrorCode::SettledScorePending);
+
+        session.game_index = session.game_index.saturating_add(1);
+        let slot_hash = most_recent_slot_hash(&ctx.accounts.slot_hashes)?;
+        let seed = spawn::game_seed(&slot_hash, session.player.as_ref(), session.game_index);
+        session.reset_for_new_game(config.max_lives, clock.unix_timestamp, seed);
+
+        msg!("New game #{} started for player: {}", session.game_index, session.player);
+
+        emit!(SessionStarted {
+            player: session.player,
+            game_index: session.game_index,
+            rng_seed: session.rng_seed,
+            timestamp: session.started_at,
+        });
+
+        Ok(())
+    }
+
     /// Initialize player profile
     pub fn initialize_profile(ctx: Context<InitializeProfile>, username: String) -> Result<()> {
         require!(username.len() <= 32, ErrorCode::UsernameTooLong);
         
         let profile = &mut ctx.accounts.player_profile;
+        profile.version = PlayerProfile::VERSION;
         profile.owner = ctx.accounts.player.key();
         profile.username = Some(username);
         profile.high_score = 0;
@@ -67,26 +117,78 @@ pub mod fruitninja {
         Ok(())
     }
 
-    /// Slice a fruit - increases score and combo
-    pub fn slice_fruit(ctx: Context<SliceFruit>, points: u64) -> Result<()> {
+    /// Spawn the next wave of fruits from the session's seeded RNG
+    pub fn spawn_wave(ctx: Context<SpawnWave>) -> Result<()> {
+        require_session_authority(
+            &ctx.accounts.session,
+            &ctx.accounts.authority,
+            &ctx.accounts.session_token,
+            SESSION_KEY_SPAWN_WAVE,
+        )?;
+        require_not_paused(&ctx.accounts.config)?;
         let session = &mut ctx.accounts.session;
+        let clock = Clock::get()?;
+        let slot = clock.slot;
         require!(session.is_active, ErrorCode::SessionNotActive);
 
-        // Validate against config max points per fruit
-        let config = &ctx.accounts.config;
-        require!(points > 0 && points <= config.max_points_per_fruit, ErrorCode::InvalidPoints);
-        
-        session.combo = session.combo.saturating_add(1);
-        if session.combo > session.max_combo {
-            session.max_combo = session.combo;
+        // Fruits of the previous wave still in play fell off screen: each
+        // non-bomb one is a miss, exactly as if miss_fruit had been called
+        for fruit_id in 0..session.wave_len {
+            if session.wave_resolved & (1 << fruit_id) != 0 {
+                continue;
+            }
+            let kind = session.take_wave_fruit(fruit_id)?;
+            if !ctx.accounts.catalogue.kind(kind)?.is_bomb() {
+                record_miss(session, fruit_id, kind, clock.unix_timestamp);
+                if !session.is_active {
+                    // Out of lives: the game is over, no new wave
+                    return Ok(());
+                }
+            }
         }
-        
-        // Use config combo multiplier base
-        let combo_multiplier = config.combo_multiplier_base.saturating_add(session.combo as u64);
-        let earned_points = points.saturating_mul(combo_multiplier).saturating_div(10);
-        
-        session.current_score = session.current_score.saturating_add(earned_points);
-        session.fruits_sliced = session.fruits_sliced.saturating_add(1);
+
+        session.wave_number = session.wave_number.saturating_add(1);
+        session.rng_seed = spawn::next_wave_seed(&session.rng_seed, session.wave_number, &session.replay_hash, slot);
+        let weights: Vec<u16> = ctx.accounts.catalogue.kinds.iter().map(|k| k.rarity_weight).collect();
+        let wave = spawn::generate_wave(&session.rng_seed, &weights)
+            .ok_or(ErrorCode::EmptyFruitCatalogue)?;
+        session.wave_kinds = wave.kinds;
+        session.wave_len = wave.len;
+        session.wave_resolved = 0;
+
+        msg!("Wave {} spawned with {} fruits", session.wave_number, wave.len);
+
+        emit!(WaveSpawned {
+            player: session.player,
+            wave_number: session.wave_number,
+            kinds: wave.kinds[..wave.len as usize].to_vec(),
+            slot,
+        });
+
+        Ok(())
+    }
+
+    /// Slice a fruit from the current wave - increases score and combo
+    pub fn slice_fruit(ctx: Context<SliceFruit>, fruit_id: u8) -> Result<()> {
+        require_session_authority(
+            &ctx.accounts.session,
+            &ctx.accounts.authority,
+            &ctx.accounts.session_token,
+            SESSION_KEY_SLICE_FRUIT,
+        )?;
+        require_not_paused(&ctx.accounts.config)?;
+        let session = &mut ctx.accounts.session;
+        let clock = Clock::get()?;
+        require!(session.is_active, ErrorCode::SessionNotActive);
+
+        let (kind, earned_points) = apply_slice(
+            session,
+            &ctx.accounts.config,
+            &ctx.accounts.catalogue,
+            fruit_id,
+            clock.unix_timestamp,
+        )?;
+        check_plausibility(session, &ctx.accounts.config, 1, clock.unix_timestamp);
 
         msg!("Fruit sliced! Points: {}, Combo: {}, Total Score: {}", 
              earned_points, session.combo, session.current_score);
@@ -94,305 +196,1264 @@ pub mod fruitninja {
         // Emit FruitSliced event
         emit!(FruitSliced {
             player: session.player,
+            wave_number: session.wave_number,
+            fruit_id,
+            kind,
             points: earned_points,
             combo: session.combo,
             total_score: session.current_score,
+            timestamp: clock.unix_timestamp,
         });
 
         Ok(())
     }
 
-    /// Lose a life when player misses or hits a bomb
-    pub fn lose_life(ctx: Context<LoseLife>) -> Result<()> {
+    /// Slice several fruits of the current wave with one swipe. Applied
+    /// atomically: each fruit scores and bumps the combo exactly as a single
+    /// slice_fruit would, then the multi-slice bonus is added once.
+    pub fn slice_fruits(ctx: Context<SliceFruit>, hits: Vec<FruitHit>) -> Result<()> {
+        require_session_authority(
+            &ctx.accounts.session,
+            &ctx.accounts.authority,
+            &ctx.accounts.session_token,
+            SESSION_KEY_SLICE_FRUIT,
+        )?;
         let session = &mut ctx.accounts.session;
+        let config = &ctx.accounts.config;
+        require_not_paused(config)?;
         let clock = Clock::get()?;
         require!(session.is_active, ErrorCode::SessionNotActive);
-        
-        if session.lives > 0 {
-            session.lives = session.lives.saturating_sub(1);
-            msg!("Life lost! Remaining lives: {}", session.lives);
+        require!(
+            !hits.is_empty() && hits.len() <= config.max_slice_batch as usize,
+            ErrorCode::InvalidSliceBatch
+        );
+
+        let mut sliced = Vec::with_capacity(hits.len());
+        for hit in &hits {
+            let (kind, points) = apply_slice(
+                session,
+                config,
+                &ctx.accounts.catalogue,
+                hit.fruit_id,
+                clock.unix_timestamp,
+            )?;
+            sliced.push(SlicedFruit {
+                fruit_id: hit.fruit_id,
+                kind,
+                points,
+            });
         }
 
-        // Reset combo
+        let bonus = config
+            .multi_slice_bonus
+            .saturating_mul(hits.len() as u64 - 1);
+        session.current_score = session.current_score.saturating_add(bonus);
+        session.record_move(ReplayAction::Bonus, hits.len() as u8, clock.unix_timestamp, bonus);
+        check_plausibility(session, config, hits.len() as u16, clock.unix_timestamp);
+
+        msg!("Swipe sliced {} fruits! Bonus: {}, Combo: {}, Total Score: {}",
+             hits.len(), bonus, session.combo, session.current_score);
+
+        emit!(FruitsSliced {
+            player: session.player,
+            wave_number: session.wave_number,
+            fruits: sliced,
+            bonus,
+            combo: session.combo,
+            total_score: session.current_score,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// A fruit of the current wave fell off screen - costs one life and the combo
+    pub fn miss_fruit(ctx: Context<MissFruit>, fruit_id: u8) -> Result<()> {
+        require_session_authority(
+            &ctx.accounts.session,
+            &ctx.accounts.authority,
+            &ctx.accounts.session_token,
+            SESSION_KEY_MISS_FRUIT,
+        )?;
+        require_not_paused(&ctx.accounts.config)?;
+        let session = &mut ctx.accounts.session;
+        let clock = Clock::get()?;
+        require!(session.is_active, ErrorCode::SessionNotActive);
+
+        let kind = session.take_wave_fruit(fruit_id)?;
+        // Letting a bomb fall is the right play, not a miss
+        require!(!ctx.accounts.catalogue.kind(kind)?.is_bomb(), ErrorCode::FruitIsBomb);
+
+        record_miss(session, fruit_id, kind, clock.unix_timestamp);
+        Ok(())
+    }
+
+    /// Slice a bomb of the current wave - applies the configured bomb penalty
+    pub fn slice_bomb(ctx: Context<SliceBomb>, fruit_id: u8) -> Result<()> {
+        require_session_authority(
+            &ctx.accounts.session,
+            &ctx.accounts.authority,
+            &ctx.accounts.session_token,
+            SESSION_KEY_SLICE_BOMB,
+        )?;
+        let session = &mut ctx.accounts.session;
+        let config = &ctx.accounts.config;
+        require_not_paused(config)?;
+        let clock = Clock::get()?;
+        require!(session.is_active, ErrorCode::SessionNotActive);
+
+        let kind = session.take_wave_fruit(fruit_id)?;
+        require!(ctx.accounts.catalogue.kind(kind)?.is_bomb(), ErrorCode::NotABomb);
+
+        session.bombs_hit = session.bombs_hit.saturating_add(1);
         session.combo = 0;
+        let score_before = session.current_score;
+        match config.bomb_penalty {
+            BombPenalty::LoseLives(n) => session.lives = session.lives.saturating_sub(n),
+            BombPenalty::LosePoints(points) => {
+                session.current_score = session.current_score.saturating_sub(points)
+            }
+            BombPenalty::EndGame => session.lives = 0,
+        }
+        let points_lost = score_before - session.current_score;
+        session.record_move(ReplayAction::Bomb, fruit_id, clock.unix_timestamp, points_lost);
+        msg!("Bomb hit! Lives: {}, Score: {}", session.lives, session.current_score);
 
-        if session.lives == 0 {
-            session.is_active = false;
-            session.ended_at = Some(clock.unix_timestamp);
-            msg!("Game Over! Final Score: {}", session.current_score);
+        emit!(BombHit {
+            player: session.player,
+            wave_number: session.wave_number,
+            fruit_id,
+            kind,
+            penalty: config.bomb_penalty,
+            points_lost,
+            lives: session.lives,
+            total_score: session.current_score,
+            timestamp: clock.unix_timestamp,
+        });
 
-            // Emit GameOver event
-            let duration = session.ended_at.unwrap_or(clock.unix_timestamp) - session.started_at;
-            emit!(GameOver {
-                player: session.player,
-                final_score: session.current_score,
-                max_combo: session.max_combo,
-                fruits_sliced: session.fruits_sliced,
-                duration,
-            });
+        if session.lives == 0 {
+            end_game(session, clock.unix_timestamp);
         }
 
         Ok(())
     }
 
+    /// Activate a power-up for its configured duration, subject to its cooldown
+    pub fn activate_powerup(ctx: Context<ActivatePowerup>, kind: PowerupKind) -> Result<()> {
+        require_session_authority(
+            &ctx.accounts.session,
+            &ctx.accounts.authority,
+            &ctx.accounts.session_token,
+            SESSION_KEY_ACTIVATE_POWERUP,
+        )?;
+        let session = &mut ctx.accounts.session;
+        let config = &ctx.accounts.config;
+        require_not_paused(config)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(session.is_active, ErrorCode::SessionNotActive);
+        require!(session.active_powerup_at(now).is_none(), ErrorCode::PowerupAlreadyActive);
+
+        let idx = kind as usize;
+        require!(now >= session.powerup_ready_at[idx], ErrorCode::PowerupOnCooldown);
+
+        session.active_powerup = Some(kind);
+        session.powerup_expires_at = now.saturating_add(config.powerup_durations[idx] as i64);
+        session.powerup_ready_at[idx] = now.saturating_add(config.powerup_cooldowns[idx] as i64);
+
+        msg!("Power-up {:?} active until {}", kind, session.powerup_expires_at);
+
+        emit!(PowerupActivated {
+            player: session.player,
+            kind,
+            expires_at: session.powerup_expires_at,
+        });
+
+        Ok(())
+    }
+
     /// End the session and update player profile and global leaderboard
-    /// NOTE: This instruction requires the GameConfig PDA (so leaderboard can be updated).
+    /// NOTE: This instruction requires the global Leaderboard PDA (not the config).
     pub fn end_session(ctx: Context<EndSession>) -> Result<()> {
+        require_session_authority(
+            &ctx.accounts.session,
+            &ctx.accounts.authority,
+            &ctx.accounts.session_token,
+            SESSION_KEY_END_SESSION,
+        )?;
         let session = &mut ctx.accounts.session;
-        let profile = &mut ctx.accounts.player_profile;
-        let config = &mut ctx.accounts.config;
+        let config = &ctx.accounts.config;
         let clock = Clock::get()?;
+
+        // Each game may be recorded into the profile and leaderboard only once
+        require!(!session.finalized, ErrorCode::SessionAlreadyEnded);
+        require_not_banned(&ctx.accounts.player_ban)?;
+        require_season_accounts(config, &ctx.accounts.season, &ctx.accounts.season_leaderboard)?;
         
         if session.is_active {
             session.is_active = false;
             session.ended_at = Some(clock.unix_timestamp);
         }
-
-        if session.current_score > profile.high_score {
-            profile.high_score = session.current_score;
-            msg!("New high score for player profile: {}!", profile.high_score);
-        }
-        
-        profile.total_games = profile.total_games.saturating_add(1);
-        profile.total_fruits_sliced = profile.total_fruits_sliced
-            .saturating_add(session.fruits_sliced);
+        let eligible = record_result(session, &mut ctx.accounts.player_profile, config, clock.unix_timestamp);
 
         msg!("Session ended. Final score: {}, Max combo: {}", 
              session.current_score, session.max_combo);
 
         // Emit GameOver event
-        let duration = session.ended_at.unwrap_or(clock.unix_timestamp) - session.started_at;
+        let ended_at = session.ended_at.unwrap_or(clock.unix_timestamp);
         emit!(GameOver {
             player: session.player,
             final_score: session.current_score,
             max_combo: session.max_combo,
             fruits_sliced: session.fruits_sliced,
-            duration,
+            duration: ended_at - session.started_at,
+            replay_hash: session.replay_hash,
         });
 
-        // Submit to global leaderboard (mutates config.leaderboard)
+        if eligible {
+            let entry = LeaderboardEntry {
+                player: session.player,
+                score: session.current_score,
+                timestamp: clock.unix_timestamp,
+            };
+            submit_to_leaderboards(
+                &mut ctx.accounts.leaderboard,
+                &ctx.accounts.season,
+                &mut ctx.accounts.season_leaderboard,
+                entry,
+                clock.unix_timestamp,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Post a score left pending by settle_session to the global board and
+    /// the active season's board. Anyone may send it once the session is back
+    /// on the base layer.
+    pub fn submit_settled_score(ctx: Context<SubmitSettledScore>) -> Result<()> {
+        let session = &mut ctx.accounts.session;
+        let clock = Clock::get()?;
+
+        require!(session.leaderboard_pending, ErrorCode::NoPendingScore);
+        require_not_banned(&ctx.accounts.player_ban)?;
+        require_season_accounts(&ctx.accounts.config, &ctx.accounts.season, &ctx.accounts.season_leaderboard)?;
+        session.leaderboard_pending = false;
+
         let entry = LeaderboardEntry {
             player: session.player,
             score: session.current_score,
             timestamp: clock.unix_timestamp,
         };
-        update_leaderboard(config, entry)?;
-
+        submit_to_leaderboards(
+            &mut ctx.accounts.leaderboard,
+            &ctx.accounts.season,
+            &mut ctx.accounts.season_leaderboard,
+            entry,
+            clock.unix_timestamp,
+        )?;
+
+        msg!("Settled score {} of {} submitted", session.current_score, session.player);
         Ok(())
     }
 
     /// Delegate the session PDA to an ER validator
-    pub fn delegate_session(ctx: Context<DelegateSession>) -> Result<()> {
+    /// The validator must be on the config allow-list.
+    pub fn delegate_session(ctx: Context<DelegateSession>, validator: Pubkey) -> Result<()> {
     let session = &ctx.accounts.session;
+    let config = &ctx.accounts.config;
+    require_not_paused(config)?;
     require!(session.is_active, ErrorCode::SessionNotActive);
+    require!(config.allowed_validators.contains(&validator), ErrorCode::InvalidDelegation);
 
     ctx.accounts.delegate_session(
         &ctx.accounts.payer,
         &[SESSION_SEED, session.player.as_ref()],
         DelegateConfig {
-            commit_frequency_ms: 30_000,
-            validator: Some(
-                "MAS1Dt9qreoRMQ14YQuhg8UTZMMzDdKhmkZMECCzk57"
-                    .parse::<Pubkey>()
-                    .unwrap(),
-            ),
-            ..Default::default()
+            commit_frequency_ms: config.commit_frequency_ms,
+            validator: Some(validator),
         },
     )?;
 
-    msg!("Session delegated to Ephemeral Rollup validator");
+    msg!("Session delegated to Ephemeral Rollup validator {}", validator);
     Ok(())
 }
 
-    /// Undelegate and commit final state
-    pub fn undelegate_session(ctx: Context<UndelegateSession>) -> Result<()> {
-    let session = &ctx.accounts.session;
+    /// Delegate the session together with the player's profile, so the
+    /// result can be recorded on the ER by settle_session
+    pub fn delegate_session_with_profile(
+        ctx: Context<DelegateSessionWithProfile>,
+        validator: Pubkey,
+    ) -> Result<()> {
+        let session = &mut ctx.accounts.session;
+        let config = &ctx.accounts.config;
+        require_not_paused(config)?;
+        require!(session.is_active, ErrorCode::SessionNotActive);
+        require!(config.allowed_validators.contains(&validator), ErrorCode::InvalidDelegation);
 
-    msg!("Undelegating session for player: {}", session.player);
+        // Every path back to the base layer must bring the profile along.
+        // Written now, since the delegation copies the account as it stands.
+        session.profile_delegated = true;
+        session.exit(&crate::ID)?;
 
-    // pass payer, vector of account infos to commit, magic_context, magic_program
-    commit_and_undelegate_accounts(
-        &ctx.accounts.payer,
-        vec![&ctx.accounts.session.to_account_info()],
-        &ctx.accounts.magic_context,
-        &ctx.accounts.magic_program,
-    )?;
+        let player = session.player;
+        let delegate_config = || DelegateConfig {
+            commit_frequency_ms: config.commit_frequency_ms,
+            validator: Some(validator),
+        };
+        ctx.accounts.delegate_session(
+            &ctx.accounts.payer,
+            &[SESSION_SEED, player.as_ref()],
+            delegate_config(),
+        )?;
+        ctx.accounts.delegate_player_profile(
+            &ctx.accounts.payer,
+            &[PROFILE_SEED, player.as_ref()],
+            delegate_config(),
+        )?;
+
+        msg!("Session and profile delegated to Ephemeral Rollup validator {}", validator);
+        Ok(())
+    }
 
-    msg!("Session successfully undelegated and committed");
-    Ok(())
-}
+    /// End the game on the ER, record it into the profile delegated with the
+    /// session (see delegate_session_with_profile) and commit + undelegate
+    /// both in one step. The leaderboards are shared by every player and stay
+    /// on the base layer, so an eligible score is left pending for
+    /// submit_settled_score.
+    pub fn settle_session(ctx: Context<SettleSession>) -> Result<()> {
+        require_session_authority(
+            &ctx.accounts.session,
+            &ctx.accounts.authority,
+            &ctx.accounts.session_token,
+            SESSION_KEY_END_SESSION,
+        )?;
+        let session = &mut ctx.accounts.session;
+        let profile = &mut ctx.accounts.player_profile;
+        let now = Clock::get()?.unix_timestamp;
+        require!(!session.finalized, ErrorCode::SessionAlreadyEnded);
+        require_not_banned(&ctx.accounts.player_ban)?;
+
+        if session.is_active {
+            end_game(session, now);
+        }
+        session.leaderboard_pending = record_result(session, profile, &ctx.accounts.config, now);
+        session.profile_delegated = false;
+
+        // Write both back before the commit snapshots them
+        session.exit(&crate::ID)?;
+        profile.exit(&crate::ID)?;
+
+        commit_to_base_layer(
+            &ctx.accounts.payer,
+            &[session.to_account_info(), profile.to_account_info()],
+            &ctx.accounts.magic_context,
+            &ctx.accounts.magic_program,
+            true,
+        )?;
+        emit_checkpoint(session);
+
+        msg!("Session for player {} settled back to the base layer", session.player);
+        Ok(())
+    }
+
+    /// Undelegate and commit final state (commit_session with `finish` set)
+    pub fn undelegate_session(ctx: Context<CommitSession>) -> Result<()> {
+        commit_session(ctx, true)
+    }
 
     /// Periodic commit while still delegated (checkpoint)
-    pub fn checkpoint_session(ctx: Context<CheckpointSession>) -> Result<()> {
-    let session = &ctx.accounts.session;
+    pub fn checkpoint_session(ctx: Context<CommitSession>) -> Result<()> {
+        commit_session(ctx, false)
+    }
 
-    // NOTE: commit_accounts expects 4 args in this SDK version:
-    // (magic_context, vec![accounts_to_commit], magic_program, payer_accountinfo)
-    commit_accounts(
-        &ctx.accounts.magic_context,
-        vec![&ctx.accounts.session.to_account_info()],
-        &ctx.accounts.magic_program,
-        &ctx.accounts.payer.to_account_info(),
-    )?;
+    /// Commit the session to the base layer. With `finish` set, the game is
+    /// ended first and the session is committed and undelegated, ready for
+    /// end_session on the base layer. A session delegated with its profile
+    /// must pass the profile to finish, so both are undelegated together.
+    pub fn commit_session(ctx: Context<CommitSession>, finish: bool) -> Result<()> {
+        require_session_authority(
+            &ctx.accounts.session,
+            &ctx.accounts.authority,
+            &ctx.accounts.session_token,
+            if finish { SESSION_KEY_END_SESSION } else { SESSION_KEY_CHECKPOINT },
+        )?;
+        let session = &mut ctx.accounts.session;
+        let profile = ctx.accounts.player_profile.as_ref().filter(|_| session.profile_delegated);
+        require!(
+            !(finish && session.profile_delegated && profile.is_none()),
+            ErrorCode::DelegatedProfileRequired
+        );
+
+        if finish && session.is_active {
+            end_game(session, Clock::get()?.unix_timestamp);
+        }
+        if finish {
+            session.profile_delegated = false;
+        }
+        // Write any change back before the commit snapshots the account
+        session.exit(&crate::ID)?;
+
+        let mut accounts = vec![session.to_account_info()];
+        accounts.extend(profile.map(|profile| profile.to_account_info()));
+        commit_to_base_layer(
+            &ctx.accounts.payer,
+            &accounts,
+            &ctx.accounts.magic_context,
+            &ctx.accounts.magic_program,
+            finish,
+        )?;
+        emit_checkpoint(session);
+
+        msg!(
+            "Checkpoint committed for player: {} | Current Score: {} | Combo: {}",
+            session.player,
+            session.current_score,
+            session.combo
+        );
+        Ok(())
+    }
 
-    msg!(
-        "Checkpoint committed for player: {} | Current Score: {} | Combo: {}",
-        session.player,
-        session.current_score,
-        session.combo
-    );
+    /// Create a session token that lets a throwaway keypair act for the player
+    /// until `valid_until`, optionally funding it to pay ER transaction fees
+    pub fn initialize_session_token(
+        ctx: Context<InitializeSessionToken>,
+        valid_until: i64,
+        allowed_instructions: u8,
+        top_up_lamports: u64,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(
+            valid_until > clock.unix_timestamp
+                && valid_until <= clock.unix_timestamp.saturating_add(MAX_SESSION_TOKEN_VALIDITY),
+            ErrorCode::InvalidSessionTokenExpiry
+        );
+        require!(
+            allowed_instructions != 0 && allowed_instructions & !SESSION_KEY_ALL == 0,
+            ErrorCode::InvalidSessionTokenPermissions
+        );
+
+        let token = &mut ctx.accounts.session_token;
+        token.player = ctx.accounts.player.key();
+        token.session_signer = ctx.accounts.session_signer.key();
+        token.valid_until = valid_until;
+        token.allowed_instructions = allowed_instructions;
+        token.bump = ctx.bumps.session_token;
+
+        if top_up_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.player.to_account_info(),
+                        to: ctx.accounts.session_signer.to_account_info(),
+                    },
+                ),
+                top_up_lamports,
+            )?;
+        }
 
-    emit!(SessionCheckpoint {
-        player: session.player,
-        current_score: session.current_score,
-        combo: session.combo,
-        fruits_sliced: session.fruits_sliced,
-    });
+        msg!(
+            "Session token created for player: {} | Signer: {} | Valid until: {}",
+            token.player,
+            token.session_signer,
+            token.valid_until
+        );
+        Ok(())
+    }
 
-    Ok(())
-}
+    /// Revoke a session token (player or the session key itself may revoke)
+    pub fn revoke_session_token(ctx: Context<RevokeSessionToken>) -> Result<()> {
+        let token = &ctx.accounts.session_token;
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == token.player || authority == token.session_signer,
+            ErrorCode::Unauthorized
+        );
 
-    /// Manual commit without undelegation (for periodic checkpoints)
-    pub fn commit_session(ctx: Context<CommitSession>) -> Result<()> {
-         commit_accounts(
-    &ctx.accounts.payer,
-    vec![&ctx.accounts.session.to_account_info()],
-    &ctx.accounts.magic_context,
-    &ctx.accounts.magic_program,
-)?;
+        msg!("Session token revoked: {}", token.session_signer);
+        Ok(())
+    }
 
-        
-        msg!("Session state committed");
+    /// Send more lamports to a session key so it can keep paying fees
+    pub fn top_up_session_token(ctx: Context<TopUpSessionToken>, lamports: u64) -> Result<()> {
+        require!(lamports > 0, ErrorCode::InvalidTopUpAmount);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.player.to_account_info(),
+                    to: ctx.accounts.session_signer.to_account_info(),
+                },
+            ),
+            lamports,
+        )?;
+
+        msg!("Session key {} topped up with {} lamports", ctx.accounts.session_signer.key(), lamports);
         Ok(())
     }
 
-    /// Admin: initialize the global config PDA (one-time)
-    pub fn initialize_config(
-        ctx: Context<InitializeConfig>,
-        max_lives: u8,
-        max_points_per_fruit: u64,
-        combo_multiplier_base: u64,
-        leaderboard_capacity: u8,
-    ) -> Result<()> {
-        require!(leaderboard_capacity as usize <= MAX_LEADERBOARD_CAPACITY, ErrorCode::LeaderboardCapacityTooLarge);
+    /// Admin: choose how the combo turns into a points multiplier
+    pub fn set_scoring_curve(ctx: Context<UpdateConfig>, curve: ScoringCurve) -> Result<()> {
         let config = &mut ctx.accounts.config;
-        config.admin = ctx.accounts.payer.key();
-        config.max_lives = max_lives;
-        config.max_points_per_fruit = max_points_per_fruit;
-        config.combo_multiplier_base = combo_multiplier_base;
-        config.leaderboard_capacity = leaderboard_capacity;
-        config.bump = ctx.bumps.config;
-        config.leaderboard = Vec::new();
+        require_role(config, &ctx.accounts.admin, ROLE_CONFIG_OPERATOR)?;
+        require!(curve.is_valid(), ErrorCode::InvalidScoringCurve);
+        validate_curve_bounds(&curve)?;
+
+        msg!("Scoring curve set: {:?}", curve);
+        let authority = ctx.accounts.admin.key();
+        set_field(&mut config.scoring_curve, curve, authority, |old, new| {
+            ConfigChange::ScoringCurve { old, new }
+        });
         Ok(())
     }
 
-    /// Admin: update global config params (only admin signer)
-    pub fn update_config(
+    /// Admin: set the anti-cheat plausibility limits (0 disables a limit)
+    pub fn set_anti_cheat_limits(
         ctx: Context<UpdateConfig>,
-        max_lives: Option<u8>,
-        max_points_per_fruit: Option<u64>,
-        combo_multiplier_base: Option<u64>,
-        leaderboard_capacity: Option<u8>,
+        max_slices_per_second: u16,
+        max_score_per_second: u64,
+        min_leaderboard_duration: i64,
     ) -> Result<()> {
         let config = &mut ctx.accounts.config;
-        // Ensure caller is admin
-        require!(ctx.accounts.admin.key() == config.admin, ErrorCode::Unauthorized);
+        require_role(config, &ctx.accounts.admin, ROLE_CONFIG_OPERATOR)?;
+        require!(min_leaderboard_duration >= 0, ErrorCode::InvalidAntiCheatLimits);
 
-        if let Some(ml) = max_lives {
-            config.max_lives = ml;
-        }
-        if let Some(mp) = max_points_per_fruit {
-            config.max_points_per_fruit = mp;
-        }
-        if let Some(cb) = combo_multiplier_base {
-            config.combo_multiplier_base = cb;
-        }
-        if let Some(cap) = leaderboard_capacity {
-            require!(cap as usize <= MAX_LEADERBOARD_CAPACITY, ErrorCode::LeaderboardCapacityTooLarge);
-            config.leaderboard_capacity = cap;
-            // If new capacity is smaller, truncate existing list
-            let cap_usize = cap as usize;
-            if config.leaderboard.len() > cap_usize {
-                config.leaderboard.truncate(cap_usize);
-            }
-            Ok(())
-        } else {
-            Ok(())
-        }
+        let authority = ctx.accounts.admin.key();
+        set_field(&mut config.max_slices_per_second, max_slices_per_second, authority, |old, new| {
+            ConfigChange::MaxSlicesPerSecond { old, new }
+        });
+        set_field(&mut config.max_score_per_second, max_score_per_second, authority, |old, new| {
+            ConfigChange::MaxScorePerSecond { old, new }
+        });
+        set_field(&mut config.min_leaderboard_duration, min_leaderboard_duration, authority, |old, new| {
+            ConfigChange::MinLeaderboardDuration { old, new }
+        });
+
+        msg!("Anti-cheat limits: {} slices/s, {} points/s, {}s minimum for the leaderboard",
+             max_slices_per_second, max_score_per_second, min_leaderboard_duration);
+        Ok(())
     }
-}
 
- pub fn get_session(ctx: Context<GetSession>) -> Result<SessionData> {
-        let session = &ctx.accounts.session;
-        Ok(SessionData {
-            player: session.player,
-            current_score: session.current_score,
-            combo: session.combo,
-            lives: session.lives,
-            is_active: session.is_active,
-            started_at: session.started_at,
-            ended_at: session.ended_at,
-            fruits_sliced: session.fruits_sliced,
-            max_combo: session.max_combo,
-        })
+    /// Admin: replace the list of ER validators sessions may be delegated to
+    pub fn set_allowed_validators(ctx: Context<UpdateConfig>, validators: Vec<Pubkey>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        // Where sessions may be delegated is security-sensitive: admin only
+        require!(ctx.accounts.admin.key() == config.admin, ErrorCode::Unauthorized);
+        require!(
+            !validators.is_empty() && validators.len() <= MAX_ALLOWED_VALIDATORS,
+            ErrorCode::InvalidDelegationConfig
+        );
+        for (i, validator) in validators.iter().enumerate() {
+            require!(!validators[..i].contains(validator), ErrorCode::InvalidDelegationConfig);
+        }
+
+        msg!("{} ER validator(s) allowed", validators.len());
+        set_field(&mut config.allowed_validators, validators, ctx.accounts.admin.key(), |old, new| {
+            ConfigChange::AllowedValidators { old, new }
+        });
+        Ok(())
     }
 
-    /// Get player profile data
-    pub fn get_profile(ctx: Context<GetProfile>) -> Result<ProfileData> {
-        let profile = &ctx.accounts.player_profile;
-        Ok(ProfileData {
-            owner: profile.owner,
-            username: profile.username.clone(),
-            high_score: profile.high_score,
-            total_games: profile.total_games,
-            total_fruits_sliced: profile.total_fruits_sliced,
-        })
+    /// Admin: set how often delegated sessions are committed to the base layer
+    pub fn set_commit_frequency(ctx: Context<UpdateConfig>, commit_frequency_ms: u32) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require_role(config, &ctx.accounts.admin, ROLE_CONFIG_OPERATOR)?;
+        require!(commit_frequency_ms > 0, ErrorCode::InvalidDelegationConfig);
+
+        set_field(&mut config.commit_frequency_ms, commit_frequency_ms, ctx.accounts.admin.key(), |old, new| {
+            ConfigChange::CommitFrequencyMs { old, new }
+        });
+        msg!("Commit frequency set to {}ms", commit_frequency_ms);
+        Ok(())
     }
 
-    /// Get game configuration
-    pub fn get_config(ctx: Context<GetConfig>) -> Result<ConfigData> {
-        let config = &ctx.accounts.config;
-        Ok(ConfigData {
+    /// Admin: initialize the global config PDA (one-time)
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        max_lives: u8,
+        max_points_per_fruit: u64,
+        combo_multiplier_base: u64,
+    ) -> Result<()> {
+        // Built with `restrict-init`, only the upgrade authority may claim the admin role
+        #[cfg(feature = "restrict-init")]
+        require_upgrade_authority(&ctx.accounts.program_data, &ctx.accounts.payer)?;
+
+        validate_game_rules(max_lives, max_points_per_fruit, combo_multiplier_base)?;
+
+        let config = &mut ctx.accounts.config;
+        config.set_inner(GameConfig::new(
+            ctx.accounts.payer.key(),
+            max_lives,
+            max_points_per_fruit,
+            combo_multiplier_base,
+            ctx.bumps.config,
+        ));
+
+        emit!(ConfigInitialized {
             admin: config.admin,
-            max_lives: config.max_lives,
-            max_points_per_fruit: config.max_points_per_fruit,
-            combo_multiplier_base: config.combo_multiplier_base,
-            leaderboard_capacity: config.leaderboard_capacity,
-        })
+            max_lives,
+            max_points_per_fruit,
+            combo_multiplier_base,
+        });
+        Ok(())
     }
 
-    /// Get full leaderboard
-    pub fn get_leaderboard(ctx: Context<GetLeaderboard>) -> Result<Vec<LeaderboardEntry>> {
-        let config = &ctx.accounts.config;
-        Ok(config.leaderboard.clone())
+    /// Moderator: take a player off a (global or season) leaderboard
+    pub fn strike_leaderboard_entry(
+        ctx: Context<StrikeLeaderboardEntry>,
+        player: Pubkey,
+        reason: u8,
+    ) -> Result<()> {
+        require_role(&ctx.accounts.config, &ctx.accounts.moderator, ROLE_MODERATOR)?;
+
+        let board = &mut ctx.accounts.leaderboard;
+        let entry = leaderboard::remove(&mut board.entries, &player)
+            .ok_or(ErrorCode::PlayerNotOnLeaderboard)?;
+
+        msg!("Struck {} ({} points) from leaderboard {}, reason {}", player, entry.score, board.board_id, reason);
+        emit!(LeaderboardEntryStruck {
+            moderator: ctx.accounts.moderator.key(),
+            board_id: board.board_id,
+            player,
+            score: entry.score,
+            reason,
+        });
+        Ok(())
     }
 
-    /// Get leaderboard entry at specific rank (0-indexed)
-    pub fn get_leaderboard_entry(
-        ctx: Context<GetLeaderboard>,
-        rank: u8,
-    ) -> Result<Option<LeaderboardEntry>> {
-        let config = &ctx.accounts.config;
-        let idx = rank as usize;
-        if idx < config.leaderboard.len() {
-            Ok(Some(config.leaderboard[idx].clone()))
-        } else {
-            Ok(None)
-        }
-    }
+    /// Moderator: reset a player's profile high score to 0
+    pub fn void_high_score(ctx: Context<VoidHighScore>, reason: u8) -> Result<()> {
+        require_role(&ctx.accounts.config, &ctx.accounts.moderator, ROLE_MODERATOR)?;
 
-    /// Get player's rank on leaderboard (returns None if not on leaderboard)
-    pub fn get_player_rank(
-        ctx: Context<GetLeaderboard>,
-        player: Pubkey,
-    ) -> Result<Option<u8>> {
-        let config = &ctx.accounts.config;
-        for (idx, entry) in config.leaderboard.iter().enumerate() {
-            if entry.player == player {
-                return Ok(Some(idx as u8));
+        let profile = &mut ctx.accounts.player_profile;
+        let high_score = profile.high_score;
+        profile.high_score = 0;
+
+        msg!("Voided high score {} of {}, reason {}", high_score, profile.owner, reason);
+        emit!(HighScoreVoided {
+            moderator: ctx.accounts.moderator.key(),
+            player: profile.owner,
+            high_score,
+            reason,
+        });
+        Ok(())
+    }
+
+    /// Moderator: ban a player. Banned players cannot start sessions or
+    /// record results with end_session.
+    pub fn ban_player(ctx: Context<BanPlayer>, player: Pubkey, reason: u8) -> Result<()> {
+        require_role(&ctx.accounts.config, &ctx.accounts.moderator, ROLE_MODERATOR)?;
+        let now = Clock::get()?.unix_timestamp;
+
+        let ban = &mut ctx.accounts.player_ban;
+        ban.player = player;
+        ban.reason = reason;
+        ban.banned_by = ctx.accounts.moderator.key();
+        ban.banned_at = now;
+        ban.bump = ctx.bumps.player_ban;
+
+        msg!("Banned {}, reason {}", player, reason);
+        emit!(PlayerBanned {
+            moderator: ban.banned_by,
+            player,
+            reason,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Moderator: lift a ban (closes the ban PDA, rent goes to the moderator)
+    pub fn unban_player(ctx: Context<UnbanPlayer>, reason: u8) -> Result<()> {
+        require_role(&ctx.accounts.config, &ctx.accounts.moderator, ROLE_MODERATOR)?;
+        let player = ctx.accounts.player_ban.player;
+
+        msg!("Unbanned {}, reason {}", player, reason);
+        emit!(PlayerUnbanned {
+            moderator: ctx.accounts.moderator.key(),
+            player,
+            reason,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Admin: emergency stop. New games, scoring and delegation are refused
+    /// until unpause; ending, settling and undelegating games keep working.
+    pub fn pause(ctx: Context<UpdateConfig>, reason: u8) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(ctx.accounts.admin.key() == config.admin, ErrorCode::Unauthorized);
+        require!(reason != PAUSE_REASON_NONE, ErrorCode::InvalidPauseReason);
+
+        let authority = ctx.accounts.admin.key();
+        set_field(&mut config.paused, true, authority, |old, new| ConfigChange::Paused { old, new });
+        set_field(&mut config.pause_reason, reason, authority, |old, new| {
+            ConfigChange::PauseReason { old, new }
+        });
+
+        msg!("Game paused (reason {})", reason);
+        Ok(())
+    }
+
+    /// Admin: lift the emergency stop
+    pub fn unpause(ctx: Context<UpdateConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(ctx.accounts.admin.key() == config.admin, ErrorCode::Unauthorized);
+
+        let authority = ctx.accounts.admin.key();
+        set_field(&mut config.paused, false, authority, |old, new| ConfigChange::Paused { old, new });
+        set_field(&mut config.pause_reason, PAUSE_REASON_NONE, authority, |old, new| {
+            ConfigChange::PauseReason { old, new }
+        });
+
+        msg!("Game unpaused");
+        Ok(())
+    }
+
+    /// Upgrade a session written before account versioning (anyone may pay
+    /// for the realloc). A game still in progress gets a fresh RNG seed.
+    pub fn migrate_session(ctx: Context<MigrateSession>, player: Pubkey) -> Result<()> {
+        let info = ctx.accounts.session.to_account_info();
+        let legacy: LegacyGameSession = migration::read_legacy(
+            &info.try_borrow_data()?,
+            GameSession::DISCRIMINATOR,
+            LegacyGameSession::LEN,
+            GameSession::LEN,
+        )?;
+        let slot_hash = most_recent_slot_hash(&ctx.accounts.slot_hashes)?;
+        let session = migration::upgrade_session(legacy, spawn::game_seed(&slot_hash, player.as_ref(), 0));
+
+        migration::store(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &session,
+            GameSession::LEN,
+        )?;
+
+        msg!("Session of {} migrated to v{}", player, GameSession::VERSION);
+        emit!(AccountMigrated {
+            account: info.key(),
+            version: GameSession::VERSION,
+        });
+        Ok(())
+    }
+
+    /// Upgrade a profile written before account versioning (anyone may pay
+    /// for the realloc)
+    pub fn migrate_profile(ctx: Context<MigrateProfile>, player: Pubkey) -> Result<()> {
+        let info = ctx.accounts.player_profile.to_account_info();
+        let legacy: LegacyPlayerProfile = migration::read_legacy(
+            &info.try_borrow_data()?,
+            PlayerProfile::DISCRIMINATOR,
+            LegacyPlayerProfile::LEN,
+            PlayerProfile::LEN,
+        )?;
+        let profile = migration::upgrade_profile(legacy);
+
+        migration::store(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &profile,
+            PlayerProfile::LEN,
+        )?;
+
+        msg!("Profile of {} migrated to v{}", player, PlayerProfile::VERSION);
+        emit!(AccountMigrated {
+            account: info.key(),
+            version: PlayerProfile::VERSION,
+        });
+        Ok(())
+    }
+
+    /// Admin: upgrade the config written before account versioning. The
+    /// leaderboard it embedded moves into the global leaderboard PDA, created
+    /// here; the config shrinks and the spare rent goes back to the admin.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let info = ctx.accounts.config.to_account_info();
+        let legacy: LegacyGameConfig = migration::read_legacy(
+            &info.try_borrow_data()?,
+            GameConfig::DISCRIMINATOR,
+            LegacyGameConfig::LEN,
+            GameConfig::LEN,
+        )?;
+        require!(ctx.accounts.admin.key() == legacy.admin, ErrorCode::Unauthorized);
+        let (mut config, entries) = migration::upgrade_config(legacy);
+        migration::clamp_game_rules(&mut config, ctx.accounts.admin.key());
+
+        let board = &mut ctx.accounts.leaderboard;
+        board.board_id = GLOBAL_LEADERBOARD_ID;
+        board.capacity = LEGACY_LEADERBOARD_CAPACITY as u16;
+        board.bump = ctx.bumps.leaderboard;
+        board.entries = entries;
+
+        migration::store(
+            &info,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &config,
+            GameConfig::LEN,
+        )?;
+
+        msg!("Config migrated to v{}, {} leaderboard entries moved", GameConfig::VERSION, board.entries.len());
+        emit!(AccountMigrated {
+            account: info.key(),
+            version: GameConfig::VERSION,
+        });
+        Ok(())
+    }
+
+    /// Admin: propose a new admin (None cancels a pending proposal). The
+    /// role only moves once the proposed key signs accept_admin.
+    pub fn propose_admin(ctx: Context<UpdateConfig>, new_admin: Option<Pubkey>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(ctx.accounts.admin.key() == config.admin, ErrorCode::Unauthorized);
+
+        set_field(&mut config.pending_admin, new_admin, ctx.accounts.admin.key(), |old, new| {
+            ConfigChange::PendingAdmin { old, new }
+        });
+        match new_admin {
+            Some(key) => msg!("Admin transfer to {} proposed", key),
+            None => msg!("Pending admin transfer cancelled"),
+        }
+        Ok(())
+    }
+
+    /// Second step of the admin transfer, signed by the proposed admin
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let new_admin = ctx.accounts.new_admin.key();
+        require!(config.pending_admin == Some(new_admin), ErrorCode::Unauthorized);
+
+        let previous = config.admin;
+        set_field(&mut config.admin, new_admin, new_admin, |old, new| ConfigChange::Admin { old, new });
+        set_field(&mut config.pending_admin, None, new_admin, |old, new| {
+            ConfigChange::PendingAdmin { old, new }
+        });
+
+        msg!("Admin transferred from {} to {}", previous, new_admin);
+        Ok(())
+    }
+
+    /// Admin: set the roles held by `authority` (0 revokes them all)
+    pub fn set_roles(ctx: Context<UpdateConfig>, authority: Pubkey, roles: u8) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(ctx.accounts.admin.key() == config.admin, ErrorCode::Unauthorized);
+        require!(roles & !ROLE_ALL == 0, ErrorCode::InvalidRoles);
+
+        let existing = config.role_grants.iter().position(|grant| grant.authority == authority);
+        let old_roles = existing.map_or(0, |idx| config.role_grants[idx].roles);
+        match (existing, roles) {
+            (Some(idx), 0) => {
+                config.role_grants.remove(idx);
             }
+            (Some(idx), _) => config.role_grants[idx].roles = roles,
+            (None, 0) => {}
+            (None, _) => {
+                require!(config.role_grants.len() < MAX_ROLE_GRANTS, ErrorCode::InvalidRoles);
+                config.role_grants.push(RoleGrant { authority, roles });
+            }
+        }
+
+        if old_roles != roles {
+            emit!(ConfigUpdated {
+                authority: ctx.accounts.admin.key(),
+                change: ConfigChange::Roles {
+                    authority,
+                    old: old_roles,
+                    new: roles,
+                },
+            });
+        }
+
+        msg!("Roles of {} set to {:#04x}", authority, roles);
+        Ok(())
+    }
+
+    /// Admin: update global config params (only admin signer)
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        max_lives: Option<u8>,
+        max_points_per_fruit: Option<u64>,
+        combo_multiplier_base: Option<u64>,
+        bomb_penalty: Option<BombPenalty>,
+        multi_slice_bonus: Option<u64>,
+        max_slice_batch: Option<u8>,
+        combo_window_ms: Option<u64>,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        // Ensure caller is the admin or a config operator
+        require_role(config, &ctx.accounts.admin, ROLE_CONFIG_OPERATOR)?;
+        let authority = ctx.accounts.admin.key();
+
+        validate_game_rules(
+            max_lives.unwrap_or(config.max_lives),
+            max_points_per_fruit.unwrap_or(config.max_points_per_fruit),
+            combo_multiplier_base.unwrap_or(config.combo_multiplier_base),
+        )?;
+
+        if let Some(ml) = max_lives {
+            set_field(&mut config.max_lives, ml, authority, |old, new| ConfigChange::MaxLives { old, new });
+        }
+        if let Some(mp) = max_points_per_fruit {
+            set_field(&mut config.max_points_per_fruit, mp, authority, |old, new| {
+                ConfigChange::MaxPointsPerFruit { old, new }
+            });
+        }
+        if let Some(cb) = combo_multiplier_base {
+            set_field(&mut config.combo_multiplier_base, cb, authority, |old, new| {
+                ConfigChange::ComboMultiplierBase { old, new }
+            });
+        }
+        if let Some(bp) = bomb_penalty {
+            require!(bp != BombPenalty::LoseLives(0), ErrorCode::InvalidBombPenalty);
+            set_field(&mut config.bomb_penalty, bp, authority, |old, new| ConfigChange::BombPenalty { old, new });
+        }
+        if let Some(bonus) = multi_slice_bonus {
+            require!(bonus <= MAX_MULTI_SLICE_BONUS, ErrorCode::MultiSliceBonusTooLarge);
+            set_field(&mut config.multi_slice_bonus, bonus, authority, |old, new| {
+                ConfigChange::MultiSliceBonus { old, new }
+            });
+        }
+        if let Some(batch) = max_slice_batch {
+            require!(
+                batch > 0 && batch as usize <= spawn::MAX_WAVE_SIZE,
+                ErrorCode::InvalidSliceBatch
+            );
+            set_field(&mut config.max_slice_batch, batch, authority, |old, new| {
+                ConfigChange::MaxSliceBatch { old, new }
+            });
+        }
+        if let Some(window) = combo_window_ms {
+            set_field(&mut config.combo_window_ms, window, authority, |old, new| {
+                ConfigChange::ComboWindowMs { old, new }
+            });
+        }
+        Ok(())
+    }
+
+    /// Admin: create the (empty) fruit catalogue PDA
+    pub fn initialize_catalogue(ctx: Context<InitializeCatalogue>) -> Result<()> {
+        require_role(&ctx.accounts.config, &ctx.accounts.admin, ROLE_CONFIG_OPERATOR)?;
+
+        let catalogue = &mut ctx.accounts.catalogue;
+        catalogue.bump = ctx.bumps.catalogue;
+        catalogue.kinds = Vec::new();
+
+        msg!("Fruit catalogue created");
+        Ok(())
+    }
+
+    /// Admin: add (kind_id == len) or replace a fruit kind. Kind ids are
+    /// stable; retire a kind by setting its rarity weight to 0.
+    pub fn set_fruit_kind(
+        ctx: Context<SetFruitKind>,
+        kind_id: u8,
+        base_points: u64,
+        rarity_weight: u16,
+        flags: u8,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require_role(config, &ctx.accounts.admin, ROLE_CONFIG_OPERATOR)?;
+        require!(flags & !FRUIT_FLAG_ALL == 0, ErrorCode::InvalidFruitKind);
+        let is_bomb = flags & FRUIT_FLAG_BOMB != 0;
+        require!(
+            base_points <= config.max_points_per_fruit && (is_bomb || base_points > 0),
+            ErrorCode::InvalidPoints
+        );
+
+        let fruit = FruitKind {
+            base_points,
+            rarity_weight,
+            flags,
+        };
+        let catalogue = &mut ctx.accounts.catalogue;
+        let idx = kind_id as usize;
+        if idx < catalogue.kinds.len() {
+            catalogue.kinds[idx] = fruit;
+        } else {
+            require!(
+                idx == catalogue.kinds.len() && idx < MAX_FRUIT_KINDS,
+                ErrorCode::InvalidFruitKind
+            );
+            catalogue.kinds.push(fruit);
         }
-        Ok(None)
+
+        msg!("Fruit kind {} set: {} points, weight {}, flags {:#04x}", kind_id, base_points, rarity_weight, flags);
+        Ok(())
+    }
+
+    /// Admin: set how long a power-up lasts and how long until it can be used again
+    pub fn set_powerup_config(
+        ctx: Context<UpdateConfig>,
+        kind: PowerupKind,
+        duration_secs: u32,
+        cooldown_secs: u32,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require_role(config, &ctx.accounts.admin, ROLE_CONFIG_OPERATOR)?;
+        require!(duration_secs > 0 && cooldown_secs >= duration_secs, ErrorCode::InvalidPowerupConfig);
+
+        let authority = ctx.accounts.admin.key();
+        set_field(&mut config.powerup_durations[kind as usize], duration_secs, authority, |old, new| {
+            ConfigChange::PowerupDuration { kind, old, new }
+        });
+        set_field(&mut config.powerup_cooldowns[kind as usize], cooldown_secs, authority, |old, new| {
+            ConfigChange::PowerupCooldown { kind, old, new }
+        });
+
+        msg!("Power-up {:?}: duration {}s, cooldown {}s", kind, duration_secs, cooldown_secs);
+        Ok(())
+    }
+
+    /// Admin: create the global leaderboard PDA
+    pub fn initialize_leaderboard(ctx: Context<InitializeLeaderboard>, capacity: u16) -> Result<()> {
+        require_role(&ctx.accounts.config, &ctx.accounts.admin, ROLE_CONFIG_OPERATOR)?;
+        require!(capacity as usize <= MAX_LEADERBOARD_CAPACITY, ErrorCode::LeaderboardCapacityTooLarge);
+        // Account creation via CPI is capped at MAX_PERMITTED_DATA_INCREASE; grow later with resize_leaderboard
+        require!(
+            Leaderboard::space(capacity as usize) <= MAX_PERMITTED_DATA_INCREASE,
+            ErrorCode::LeaderboardGrowthTooLarge
+        );
+
+        let board = &mut ctx.accounts.leaderboard;
+        board.board_id = GLOBAL_LEADERBOARD_ID;
+        board.capacity = capacity;
+        board.bump = ctx.bumps.leaderboard;
+        board.entries = Vec::new();
+
+        msg!("Leaderboard {} created with capacity {}", board.board_id, capacity);
+        Ok(())
+    }
+
+    /// Admin: grow or shrink a leaderboard (realloc happens in the account constraints)
+    pub fn resize_leaderboard(ctx: Context<ResizeLeaderboard>, new_capacity: u16) -> Result<()> {
+        let board = &mut ctx.accounts.leaderboard;
+        // Season boards belong to the season manager, the global one to config operators
+        let role = if board.board_id == GLOBAL_LEADERBOARD_ID {
+            ROLE_CONFIG_OPERATOR
+        } else {
+            ROLE_SEASON_MANAGER
+        };
+        require_role(&ctx.accounts.config, &ctx.accounts.admin, role)?;
+        require!(new_capacity as usize <= MAX_LEADERBOARD_CAPACITY, ErrorCode::LeaderboardCapacityTooLarge);
+
+        require!(
+            board.board_id == GLOBAL_LEADERBOARD_ID
+                || new_capacity as usize <= MAX_SEASON_LEADERBOARD_CAPACITY,
+            ErrorCode::LeaderboardCapacityTooLarge
+        );
+        require!(
+            Leaderboard::space(new_capacity as usize)
+                <= Leaderboard::space(board.capacity as usize) + MAX_PERMITTED_DATA_INCREASE,
+            ErrorCode::LeaderboardGrowthTooLarge
+        );
+
+        board.capacity = new_capacity;
+        // If new capacity is smaller, truncate existing list
+        board.entries.truncate(new_capacity as usize);
+
+        msg!("Leaderboard {} resized to capacity {}", board.board_id, new_capacity);
+        Ok(())
+    }
+
+    /// Admin: open the next season with its own leaderboard
+    pub fn create_season(
+        ctx: Context<CreateSeason>,
+        season_id: u32,
+        start_ts: i64,
+        end_ts: i64,
+        capacity: u16,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require_role(config, &ctx.accounts.admin, ROLE_SEASON_MANAGER)?;
+        require!(config.active_season == NO_ACTIVE_SEASON, ErrorCode::SeasonStillActive);
+        require!(season_id == config.season_count.saturating_add(1), ErrorCode::InvalidSeason);
+        require!(start_ts < end_ts, ErrorCode::InvalidSeasonWindow);
+        require!(capacity as usize <= MAX_SEASON_LEADERBOARD_CAPACITY, ErrorCode::LeaderboardCapacityTooLarge);
+
+        let season = &mut ctx.accounts.season;
+        season.season_id = season_id;
+        season.start_ts = start_ts;
+        season.end_ts = end_ts;
+        season.closed_at = None;
+        season.bump = ctx.bumps.season;
+
+        let board = &mut ctx.accounts.season_leaderboard;
+        board.board_id = season_id;
+        board.capacity = capacity;
+        board.bump = ctx.bumps.season_leaderboard;
+        board.entries = Vec::new();
+
+        let authority = ctx.accounts.admin.key();
+        set_field(&mut config.season_count, season_id, authority, |old, new| {
+            ConfigChange::SeasonCount { old, new }
+        });
+        set_field(&mut config.active_season, season_id, authority, |old, new| {
+            ConfigChange::ActiveSeason { old, new }
+        });
+
+        msg!("Season {} created: {} -> {}", season_id, start_ts, end_ts);
+
+        emit!(SeasonCreated {
+            season_id,
+            start_ts,
+            end_ts,
+        });
+
+        Ok(())
+    }
+
+    /// Admin: close the active season and freeze its standings into a
+    /// SeasonArchive. The live season board is closed and its rent refunded.
+    pub fn close_season(ctx: Context<CloseSeason>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require_role(config, &ctx.accounts.admin, ROLE_SEASON_MANAGER)?;
+
+        let clock = Clock::get()?;
+        let season = &mut ctx.accounts.season;
+        require!(season.season_id == config.active_season, ErrorCode::InvalidSeason);
+
+        season.closed_at = Some(clock.unix_timestamp);
+        set_field(&mut config.active_season, NO_ACTIVE_SEASON, ctx.accounts.admin.key(), |old, new| {
+            ConfigChange::ActiveSeason { old, new }
+        });
+
+        let archive = &mut ctx.accounts.archive;
+        archive.season_id = season.season_id;
+        archive.start_ts = season.start_ts;
+        archive.end_ts = season.end_ts;
+        archive.closed_at = clock.unix_timestamp;
+        archive.bump = ctx.bumps.archive;
+        archive.entries = ctx.accounts.season_leaderboard.entries.clone();
+
+        msg!("Season {} closed with {} entries", season.season_id, archive.entries.len());
+
+        emit!(SeasonClosed {
+            season_id: season.season_id,
+            entries: archive.entries.len() as u16,
+            closed_at: archive.closed_at,
+        });
+
+        Ok(())
+    }
+
+    /// Get session data
+    pub fn get_session(ctx: Context<GetSession>) -> Result<SessionData> {
+        let session = &ctx.accounts.session;
+        Ok(SessionData {
+            player: session.player,
+            current_score: session.current_score,
+            combo: session.combo,
+            lives: session.lives,
+            is_active: session.is_active,
+            started_at: session.started_at,
+            ended_at: session.ended_at,
+            fruits_sliced: session.fruits_sliced,
+            max_combo: session.max_combo,
+            game_index: session.game_index,
+            finalized: session.finalized,
+            wave_number: session.wave_number,
+            active_powerup: session.active_powerup,
+            powerup_expires_at: session.powerup_expires_at,
+            suspicious: session.suspicious,
+            replay_hash: session.replay_hash,
+            leaderboard_pending: session.leaderboard_pending,
+            profile_delegated: session.profile_delegated,
+        })
+    }
+
+    /// Get player profile data
+    pub fn get_profile(ctx: Context<GetProfile>) -> Result<ProfileData> {
+        let profile = &ctx.accounts.player_profile;
+        Ok(ProfileData {
+            owner: profile.owner,
+            username: profile.username.clone(),
+            high_score: profile.high_score,
+            total_games: profile.total_games,
+            total_fruits_sliced: profile.total_fruits_sliced,
+        })
+    }
+
+    /// Get game configuration
+    pub fn get_config(ctx: Context<GetConfig>) -> Result<ConfigData> {
+        let config = &ctx.accounts.config;
+        Ok(ConfigData {
+            admin: config.admin,
+            max_lives: config.max_lives,
+            max_points_per_fruit: config.max_points_per_fruit,
+            combo_multiplier_base: config.combo_multiplier_base,
+            active_season: config.active_season,
+            bomb_penalty: config.bomb_penalty,
+            multi_slice_bonus: config.multi_slice_bonus,
+            max_slice_batch: config.max_slice_batch,
+            combo_window_ms: config.combo_window_ms,
+            scoring_curve: config.scoring_curve.clone(),
+            max_slices_per_second: config.max_slices_per_second,
+            max_score_per_second: config.max_score_per_second,
+            min_leaderboard_duration: config.min_leaderboard_duration,
+            allowed_validators: config.allowed_validators.clone(),
+            commit_frequency_ms: config.commit_frequency_ms,
+            pending_admin: config.pending_admin,
+            role_grants: config.role_grants.clone(),
+            paused: config.paused,
+            pause_reason: config.pause_reason,
+        })
+    }
+
+    /// Get a page of the leaderboard (return data is capped, so at most
+    /// MAX_LEADERBOARD_PAGE entries per call)
+    pub fn get_leaderboard(
+        ctx: Context<GetLeaderboard>,
+        offset: u16,
+        limit: u8,
+    ) -> Result<Vec<LeaderboardEntry>> {
+        let board = &ctx.accounts.leaderboard;
+        let limit = (limit as usize).min(MAX_LEADERBOARD_PAGE);
+        Ok(board
+            .entries
+            .iter()
+            .skip(offset as usize)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    /// Get leaderboard entry at specific rank (0-indexed)
+    pub fn get_leaderboard_entry(
+        ctx: Context<GetLeaderboard>,
+        rank: u16,
+    ) -> Result<Option<LeaderboardEntry>> {
+        let board = &ctx.accounts.leaderboard;
+        Ok(board.entries.get(rank as usize).cloned())
+    }
+
+    /// Get player's rank on leaderboard (returns None if not on leaderboard)
+    pub fn get_player_rank(
+        ctx: Context<GetLeaderboard>,
+        player: Pubkey,
+    ) -> Result<Option<u16>> {
+        let board = &ctx.accounts.leaderboard;
+        Ok(leaderboard::rank_of(&board.entries, &player).map(|idx| idx as u16))
+    }
+
+    /// Get a page of a closed season's frozen standings
+    pub fn get_season_archive(
+        ctx: Context<GetSeasonArchive>,
+        offset: u16,
+        limit: u8,
+    ) -> Result<Vec<LeaderboardEntry>> {
+        let archive = &ctx.accounts.archive;
+        let limit = (limit as usize).min(MAX_LEADERBOARD_PAGE);
+        Ok(archive
+            .entries
+            .iter()
+            .skip(offset as usize)
+            .take(limit)
+            .cloned()
+            .collect())
     }
 
     /// Get session statistics (score, combo, fruits sliced)
@@ -404,6 +1465,8 @@ pub mod fruitninja {
             max_combo: session.max_combo,
             fruits_sliced: session.fruits_sliced,
             lives: session.lives,
+            fruits_missed: session.fruits_missed,
+            bombs_hit: session.bombs_hit,
         })
     }
 
@@ -423,17 +1486,22 @@ pub mod fruitninja {
     /// Calculate potential points for a fruit slice with current combo
     pub fn calculate_slice_points(
         ctx: Context<CalculatePoints>,
-        base_points: u64,
+        kind: u8,
     ) -> Result<u64> {
         let session = &ctx.accounts.session;
         let config = &ctx.accounts.config;
-        
-        require!(base_points > 0 && base_points <= config.max_points_per_fruit, ErrorCode::InvalidPoints);
-        
-        let combo_multiplier = config.combo_multiplier_base.saturating_add(session.combo as u64);
-        let earned_points = base_points.saturating_mul(combo_multiplier).saturating_div(10);
-        
-    Ok(earned_points)
+        let clock = Clock::get()?;
+
+        let fruit = ctx.accounts.catalogue.kind(kind)?;
+        require!(!fruit.is_bomb(), ErrorCode::FruitIsBomb);
+
+        Ok(slice_points(config, session, fruit.base_points, clock.unix_timestamp))
+    }
+
+    /// Get the fruit catalogue (index = kind id)
+    pub fn get_fruit_catalogue(ctx: Context<GetFruitCatalogue>) -> Result<Vec<FruitKind>> {
+        Ok(ctx.accounts.catalogue.kinds.clone())
+    }
 }
 
 // =================== Constants & Helpers ===================
@@ -441,88 +1509,857 @@ pub mod fruitninja {
 pub const SESSION_SEED: &[u8] = b"session";
 pub const PROFILE_SEED: &[u8] = b"profile";
 pub const CONFIG_SEED: &[u8] = b"config";
-
-// Maximum leaderboard size we preallocate storage for (compile-time constant)
-pub const MAX_LEADERBOARD_CAPACITY: usize = 20;
+pub const SESSION_TOKEN_SEED: &[u8] = b"session_token";
+pub const CATALOGUE_SEED: &[u8] = b"catalogue";
+pub const BAN_SEED: &[u8] = b"ban";
+
+// Zeroed padding at the end of each versioned account, so new fields can be
+// added without a realloc (see the migration module)
+pub const SESSION_RESERVED: usize = 62;
+pub const PROFILE_RESERVED: usize = 32;
+pub const CONFIG_RESERVED: usize = 64;
+
+// Fruit kinds the catalogue can hold; kind ids index into it
+pub const MAX_FRUIT_KINDS: usize = 16;
+
+// Bit flags for FruitKind::flags
+pub const FRUIT_FLAG_BOMB: u8 = 1 << 0;
+pub const FRUIT_FLAG_GOLDEN: u8 = 1 << 1;
+pub const FRUIT_FLAG_FROZEN: u8 = 1 << 2;
+pub const FRUIT_FLAG_ALL: u8 = FRUIT_FLAG_BOMB | FRUIT_FLAG_GOLDEN | FRUIT_FLAG_FROZEN;
+
+// Instructions a session key may sign (bit flags for SessionToken::allowed_instructions)
+pub const SESSION_KEY_SLICE_FRUIT: u8 = 1 << 0;
+pub const SESSION_KEY_MISS_FRUIT: u8 = 1 << 1;
+pub const SESSION_KEY_CHECKPOINT: u8 = 1 << 2;
+pub const SESSION_KEY_END_SESSION: u8 = 1 << 3;
+pub const SESSION_KEY_SPAWN_WAVE: u8 = 1 << 4;
+pub const SESSION_KEY_SLICE_BOMB: u8 = 1 << 5;
+pub const SESSION_KEY_ACTIVATE_POWERUP: u8 = 1 << 6;
+pub const SESSION_KEY_ALL: u8 = SESSION_KEY_SLICE_FRUIT
+    | SESSION_KEY_MISS_FRUIT
+    | SESSION_KEY_CHECKPOINT
+    | SESSION_KEY_END_SESSION
+    | SESSION_KEY_SPAWN_WAVE
+    | SESSION_KEY_SLICE_BOMB
+    | SESSION_KEY_ACTIVATE_POWERUP;
+
+// Bounds for the core game rules in GameConfig. The combo base is in tenths
+// (10 = 1.0x), so 100 caps the starting multiplier at 10x.
+pub const MAX_LIVES_LIMIT: u8 = 10;
+pub const MAX_POINTS_PER_FRUIT_LIMIT: u64 = 10_000;
+pub const MAX_COMBO_MULTIPLIER_BASE: u64 = 100;
+
+// Bounds for the scoring parameters, so no setting can saturate scores.
+// Curve values are in tenths like the combo base: a linear step of 10 adds
+// 1.0x per combo, and stepped bonuses and exponential caps stop at 100x.
+pub const MAX_MULTI_SLICE_BONUS: u64 = MAX_POINTS_PER_FRUIT_LIMIT;
+pub const MAX_CURVE_STEP: u64 = 10;
+pub const MAX_CURVE_BONUS: u64 = 1_000;
+pub const MAX_CURVE_CAP: u64 = 1_000;
+
+// Why the game is paused (GameConfig::pause_reason). Codes above these are
+// free for operators to define.
+pub const PAUSE_REASON_NONE: u8 = 0;
+pub const PAUSE_REASON_MAINTENANCE: u8 = 1;
+pub const PAUSE_REASON_EXPLOIT: u8 = 2;
+pub const PAUSE_REASON_ROLLUP_OUTAGE: u8 = 3;
+
+// Roles the admin can grant (bit flags for RoleGrant::roles). The admin
+// implicitly holds all of them.
+pub const ROLE_CONFIG_OPERATOR: u8 = 1 << 0;
+pub const ROLE_SEASON_MANAGER: u8 = 1 << 1;
+pub const ROLE_MODERATOR: u8 = 1 << 2;
+pub const ROLE_ALL: u8 = ROLE_CONFIG_OPERATOR | ROLE_SEASON_MANAGER | ROLE_MODERATOR;
+pub const MAX_ROLE_GRANTS: usize = 8;
+
+// Power-up timings in seconds, indexed by PowerupKind
+// (slow motion, freeze time, double points)
+pub const POWERUP_KIND_COUNT: usize = 3;
+pub const DEFAULT_POWERUP_DURATIONS: [u32; POWERUP_KIND_COUNT] = [8, 5, 10];
+pub const DEFAULT_POWERUP_COOLDOWNS: [u32; POWERUP_KIND_COUNT] = [30, 30, 45];
+pub const DOUBLE_POINTS_MULTIPLIER: u64 = 2;
+
+// Multi-slice defaults: flat bonus per extra fruit in one swipe, and swipe size cap
+pub const DEFAULT_MULTI_SLICE_BONUS: u64 = 5;
+pub const DEFAULT_MAX_SLICE_BATCH: u8 = 5;
+
+// Combo resets if no fruit is sliced within this window (0 = never decays)
+pub const DEFAULT_COMBO_WINDOW_MS: u64 = 3_000;
+
+// Anti-cheat defaults: slices in one clock second, points per second of play
+// (0 = unchecked until tuned to the catalogue), and the shortest game that may
+// enter a leaderboard
+pub const DEFAULT_MAX_SLICES_PER_SECOND: u16 = 20;
+pub const DEFAULT_MAX_SCORE_PER_SECOND: u64 = 0;
+pub const DEFAULT_MIN_LEADERBOARD_DURATION: i64 = 10;
+
+// Ephemeral Rollup delegation: validators sessions may be delegated to, and
+// how often a delegated session is committed back
+pub const MAX_ALLOWED_VALIDATORS: usize = 4;
+pub const DEFAULT_ER_VALIDATOR: Pubkey = pubkey!("MAS1Dt9qreoRMQ14YQuhg8UTZMMzDdKhmkZMECCzk57");
+pub const DEFAULT_COMMIT_FREQUENCY_MS: u32 = 30_000;
+
+// Session tokens are short-lived by design (7 days max)
+pub const MAX_SESSION_TOKEN_VALIDITY: i64 = 7 * 24 * 60 * 60;
+
+pub const LEADERBOARD_SEED: &[u8] = b"leaderboard";
+pub const SEASON_SEED: &[u8] = b"season";
+pub const SEASON_ARCHIVE_SEED: &[u8] = b"season_archive";
+
+// Leaderboards are sharded by id; id 0 is the all-time global board
+pub const GLOBAL_LEADERBOARD_ID: u32 = 0;
+// Season ids start at 1 and double as their leaderboard id; 0 means no season
+pub const NO_ACTIVE_SEASON: u32 = 0;
+// Upper bound for a single board. The Vec-backed board is deserialized and
+// reserialized on every end_session, so the cost grows with its size; 200
+// entries (~9.6KB) keeps a full board well inside the compute budget
+pub const MAX_LEADERBOARD_CAPACITY: usize = 200;
 // Entry size: Pubkey (32) + u64 (8) + i64 (8) = 48 bytes
 pub const LEADERBOARD_ENTRY_SIZE: usize = 32 + 8 + 8;
-
-// Compute GameConfig space:
-// discriminator (8) + admin(32) + max_lives(1) + max_points(8) + combo_base(8)
-// + leaderboard_capacity(1) + bump(1) + vec prefix (4) + MAX_LEADERBOARD_CAPACITY * ENTRY_SIZE + padding (8)
-pub const GAME_CONFIG_MAX_LEN: usize = 8 + 32 + 1 + 8 + 8 + 1 + 1 + 4 + (MAX_LEADERBOARD_CAPACITY * LEADERBOARD_ENTRY_SIZE) + 8;
-
-// Helper to update the leaderboard in-place (keeps it sorted desc and truncated)
-fn update_leaderboard(config: &mut Account<GameConfig>, new_entry: LeaderboardEntry) -> Result<()> {
-    let cap = config.leaderboard_capacity as usize;
-    // Insert
-    config.leaderboard.push(new_entry);
-    // Sort descending
-    config.leaderboard.sort_by(|a, b| b.score.cmp(&a.score));
-    // Truncate to configured capacity
-    if config.leaderboard.len() > cap {
-        config.leaderboard.truncate(cap);
+// Season boards are frozen into an archive created via CPI, which caps the
+// archive at MAX_PERMITTED_DATA_INCREASE bytes
+pub const MAX_SEASON_LEADERBOARD_CAPACITY: usize = 200;
+// Entries per get_leaderboard call; keeps return data under the 1024-byte limit
+pub const MAX_LEADERBOARD_PAGE: usize = 20;
+
+// Helper to update the leaderboard in-place (one slot per player, best-first, truncated)
+fn update_leaderboard(board: &mut Account<Leaderboard>, new_entry: LeaderboardEntry) -> Result<()> {
+    let cap = board.capacity as usize;
+    if leaderboard::submit(&mut board.entries, new_entry, cap) {
+        msg!("Leaderboard {} updated", board.board_id);
     }
     Ok(())
 }
 
-// =================== Account Types ===================
+// Bounds shared by initialize_config and update_config (migrate_config clamps into them)
+fn validate_game_rules(max_lives: u8, max_points_per_fruit: u64, combo_multiplier_base: u64) -> Result<()> {
+    require!((1..=MAX_LIVES_LIMIT).contains(&max_lives), ErrorCode::InvalidMaxLives);
+    require!(
+        (1..=MAX_POINTS_PER_FRUIT_LIMIT).contains(&max_points_per_fruit),
+        ErrorCode::InvalidMaxPointsPerFruit
+    );
+    require!(
+        (1..=MAX_COMBO_MULTIPLIER_BASE).contains(&combo_multiplier_base),
+        ErrorCode::InvalidComboMultiplierBase
+    );
+    Ok(())
+}
 
-#[account]
-pub struct GameSession {
-    pub player: Pubkey,           // 32
-    pub bump: u8,                 // 1
-    pub current_score: u64,       // 8
-    pub combo: u8,                // 1
-    pub lives: u8,                // 1
-    pub is_active: bool,          // 1
-    pub started_at: i64,          // 8
-    pub ended_at: Option<i64>,    // 1 + 8
-    pub fruits_sliced: u64,       // 8
-    pub max_combo: u8,            // 1
+// Upper bounds on a scoring curve's parameters (shape is checked by is_valid)
+fn validate_curve_bounds(curve: &ScoringCurve) -> Result<()> {
+    match curve {
+        ScoringCurve::Linear { step } => require!(*step <= MAX_CURVE_STEP, ErrorCode::CurveStepTooLarge),
+        ScoringCurve::Stepped { tiers } => require!(
+            tiers.iter().all(|tier| tier.bonus <= MAX_CURVE_BONUS),
+            ErrorCode::CurveBonusTooLarge
+        ),
+        ScoringCurve::CappedExponential { cap, .. } => require!(*cap <= MAX_CURVE_CAP, ErrorCode::CurveCapTooLarge),
+    }
+    Ok(())
 }
 
-// Size: use earlier value (still reasonable)
-impl GameSession {
-    pub const LEN: usize = 8 + 32 + 1 + 8 + 1 + 1 + 1 + 8 + 9 + 8 + 1;
+// Write a config field and, if its value actually changed, emit a
+// ConfigUpdated event with the old and new values
+fn set_field<T: PartialEq + Clone>(
+    field: &mut T,
+    value: T,
+    authority: Pubkey,
+    change: impl FnOnce(T, T) -> ConfigChange,
+) {
+    if *field != value {
+        let old = core::mem::replace(field, value.clone());
+        emit!(ConfigUpdated {
+            authority,
+            change: change(old, value),
+        });
+    }
 }
 
-#[account]
-pub struct PlayerProfile {
-    pub owner: Pubkey,                  // 32
-    pub username: Option<String>,       // 1 + 4 + 32 (max)
-    pub high_score: u64,                // 8
-    pub total_games: u64,               // 8
-    pub total_fruits_sliced: u64,       // 8
-    pub bump: u8,                       // 1
+// A player is banned while their ban PDA exists
+fn require_not_banned(player_ban: &UncheckedAccount) -> Result<()> {
+    require!(player_ban.data_is_empty(), ErrorCode::PlayerBanned);
+    Ok(())
 }
 
-impl PlayerProfile {
-    pub const LEN: usize = 8 + 32 + 37 + 8 + 8 + 8 + 1;
+// Authorize an admin instruction: the admin, or a key granted `role`
+fn require_role(config: &GameConfig, signer: &Signer, role: u8) -> Result<()> {
+    require!(config.has_role(&signer.key(), role), ErrorCode::Unauthorized);
+    Ok(())
 }
 
-#[account]
-pub struct GameConfig {
-    pub admin: Pubkey,                  // 32
+// The payer must be the upgrade authority recorded in this program's
+// ProgramData account
+#[cfg(feature = "restrict-init")]
+fn require_upgrade_authority(program_data: &Option<Account<ProgramData>>, payer: &Signer) -> Result<()> {
+    let program_data = program_data.as_ref().ok_or(ErrorCode::Unauthorized)?;
+    let (expected, _) = Pubkey::find_program_address(&[crate::ID.as_ref()], &bpf_loader_upgradeable::ID);
+    require!(
+        program_data.key() == expected
+            && program_data.upgrade_authority_address == Some(payer.key()),
+        ErrorCode::Unauthorized
+    );
+    Ok(())
+}
+
+// Authorize a gameplay instruction: either the player signed it, or a live
+// session token for the signing key permits `instruction`. Only reads the
+// (non-delegated) token, so this works inside the ER as well.
+fn require_session_authority(
+    session: &GameSession,
+    authority: &Signer,
+    session_token: &Option<Account<SessionToken>>,
+    instruction: u8,
+) -> Result<()> {
+    if authority.key() == session.player {
+        return Ok(());
+    }
+
+    let token = session_token.as_ref().ok_or(ErrorCode::Unauthorized)?;
+    require!(
+        token.player == session.player && token.session_signer == authority.key(),
+        ErrorCode::InvalidSessionToken
+    );
+    require!(
+        token.allowed_instructions & instruction != 0,
+        ErrorCode::SessionTokenInstructionNotAllowed
+    );
+    let clock = Clock::get()?;
+    require!(clock.unix_timestamp < token.valid_until, ErrorCode::SessionTokenExpired);
+    Ok(())
+}
+
+// Points for slicing a fruit worth `base_points` at `now`, scored at the combo
+// the slice brings the session to. Shared by slice_fruit and
+// calculate_slice_points so both agree; call it before the combo is bumped.
+fn slice_points(config: &GameConfig, session: &GameSession, base_points: u64, now: i64) -> u64 {
+    let powerup_multiplier = if session.active_powerup_at(now) == Some(PowerupKind::DoublePoints) {
+        DOUBLE_POINTS_MULTIPLIER
+    } else {
+        1
+    };
+    scoring::slice_points(
+        &config.scoring_curve,
+        config.combo_multiplier_base,
+        // A kind written before the cap was lowered still scores at most the cap
+        base_points.min(config.max_points_per_fruit),
+        session.next_combo(now, config.combo_window_ms),
+        powerup_multiplier,
+    )
+}
+
+// Slice one fruit of the current wave: resolve it, bump the combo and add its
+// points. Points come from the fruit's kind in the catalogue, never from the client.
+fn apply_slice(
+    session: &mut GameSession,
+    config: &GameConfig,
+    catalogue: &FruitCatalogue,
+    fruit_id: u8,
+    now: i64,
+) -> Result<(u8, u64)> {
+    let kind = session.take_wave_fruit(fruit_id)?;
+    let fruit = catalogue.kind(kind)?;
+    require!(!fruit.is_bomb(), ErrorCode::FruitIsBomb);
+
+    // Combo multiplier from the configured curve, doubled while Double Points is active
+    let earned_points = slice_points(config, session, fruit.base_points, now);
+
+    session.combo = session.next_combo(now, config.combo_window_ms);
+    if session.combo > session.max_combo {
+        session.max_combo = session.combo;
+    }
+    session.last_slice_at = now;
+
+    session.current_score = session.current_score.saturating_add(earned_points);
+    session.fruits_sliced = session.fruits_sliced.saturating_add(1);
+    session.record_move(ReplayAction::Slice, fruit_id, now, earned_points);
+    Ok((kind, earned_points))
+}
+
+// Anti-cheat: count slices in the current clock second and check the score
+// rate. A session that breaks a limit is flagged, not rejected, so the game
+// plays on but its score never reaches a leaderboard.
+fn check_plausibility(session: &mut GameSession, config: &GameConfig, slices: u16, now: i64) {
+    if session.slice_window_start != now {
+        session.slice_window_start = now;
+        session.slices_in_window = 0;
+    }
+    session.slices_in_window = session.slices_in_window.saturating_add(slices);
+
+    if config.max_slices_per_second > 0 && session.slices_in_window > config.max_slices_per_second {
+        flag_suspicious(session, SuspicionReason::SliceRate, now);
+    }
+    if exceeds_score_rate(session, config, now) {
+        flag_suspicious(session, SuspicionReason::ScoreRate, now);
+    }
+}
+
+// More points than max_score_per_second allows for the time played so far
+fn exceeds_score_rate(session: &GameSession, config: &GameConfig, now: i64) -> bool {
+    let elapsed = now.saturating_sub(session.started_at).max(1) as u64;
+    config.max_score_per_second > 0
+        && session.current_score > config.max_score_per_second.saturating_mul(elapsed)
+}
+
+// Mark the session suspicious; only the first broken limit is reported
+fn flag_suspicious(session: &mut GameSession, reason: SuspicionReason, now: i64) {
+    if session.suspicious {
+        return;
+    }
+    session.suspicious = true;
+    msg!("Session flagged as suspicious: {:?}", reason);
+
+    emit!(SuspiciousSession {
+        player: session.player,
+        game_index: session.game_index,
+        reason,
+        score: session.current_score,
+        timestamp: now,
+    });
+}
+
+// Announce state that was just committed to the base layer
+fn emit_checkpoint(session: &GameSession) {
+    emit!(SessionCheckpoint {
+        player: session.player,
+        current_score: session.current_score,
+        combo: session.combo,
+        fruits_sliced: session.fruits_sliced,
+        replay_hash: session.replay_hash,
+    });
+}
+
+// Record a finished game: flag an implausible score rate, fold the result into
+// the profile and finalize the session. Returns whether the score may enter
+// the leaderboards; flagged or too-short games stay off them.
+fn record_result(session: &mut GameSession, profile: &mut PlayerProfile, config: &GameConfig, now: i64) -> bool {
+    session.finalized = true;
+
+    let ended_at = session.ended_at.unwrap_or(now);
+    if exceeds_score_rate(session, config, ended_at) {
+        flag_suspicious(session, SuspicionReason::ScoreRate, now);
+    }
+
+    if session.current_score > profile.high_score {
+        profile.high_score = session.current_score;
+        msg!("New high score for player profile: {}!", profile.high_score);
+    }
+    profile.total_games = profile.total_games.saturating_add(1);
+    profile.total_fruits_sliced = profile.total_fruits_sliced
+        .saturating_add(session.fruits_sliced);
+
+    let duration = ended_at - session.started_at;
+    if session.suspicious {
+        msg!("Session flagged as suspicious, score kept off the leaderboards");
+        return false;
+    }
+    if duration < config.min_leaderboard_duration {
+        msg!("Session lasted {}s (minimum {}s), score kept off the leaderboards",
+             duration, config.min_leaderboard_duration);
+        return false;
+    }
+    true
+}
+
+// While a season runs its accounts are mandatory, so the caller cannot keep a
+// score off the season board by leaving them out
+fn require_season_accounts(
+    config: &GameConfig,
+    season: &Option<Account<Season>>,
+    season_board: &Option<Account<Leaderboard>>,
+) -> Result<()> {
+    match (season, season_board) {
+        (Some(season), Some(season_board)) => require!(
+            season.season_id == config.active_season && season_board.board_id == season.season_id,
+            ErrorCode::InvalidSeason
+        ),
+        _ => require!(config.active_season == NO_ACTIVE_SEASON, ErrorCode::SeasonAccountsRequired),
+    }
+    Ok(())
+}
+
+// Submit a score to the global board and, while it is running, to the active
+// season's board (checked by require_season_accounts)
+fn submit_to_leaderboards(
+    board: &mut Account<Leaderboard>,
+    season: &Option<Account<Season>>,
+    season_board: &mut Option<Account<Leaderboard>>,
+    entry: LeaderboardEntry,
+    now: i64,
+) -> Result<()> {
+    update_leaderboard(board, entry.clone())?;
+    if let (Some(season), Some(season_board)) = (season, season_board) {
+        if season.is_open_at(now) {
+            update_leaderboard(season_board, entry)?;
+        } else {
+            msg!("Season {} is not running, score kept off the season board", season.season_id);
+        }
+    }
+    Ok(())
+}
+
+// A wave fruit fell off screen: break the combo and take a life, ending the
+// game on the last one. Shared by miss_fruit and spawn_wave.
+fn record_miss(session: &mut GameSession, fruit_id: u8, kind: u8, now: i64) {
+    session.fruits_missed = session.fruits_missed.saturating_add(1);
+    session.combo = 0;
+    session.lives = session.lives.saturating_sub(1);
+    session.record_move(ReplayAction::Miss, fruit_id, now, 0);
+    msg!("Fruit missed! Remaining lives: {}", session.lives);
+
+    emit!(FruitMissed {
+        player: session.player,
+        wave_number: session.wave_number,
+        fruit_id,
+        kind,
+        lives: session.lives,
+        timestamp: now,
+    });
+
+    if session.lives == 0 {
+        end_game(session, now);
+    }
+}
+
+// Out of lives: close the game and announce the result
+fn end_game(session: &mut GameSession, now: i64) {
+    session.is_active = false;
+    session.ended_at = Some(now);
+    msg!("Game Over! Final Score: {}", session.current_score);
+
+    emit!(GameOver {
+        player: session.player,
+        final_score: session.current_score,
+        max_combo: session.max_combo,
+        fruits_sliced: session.fruits_sliced,
+        duration: now - session.started_at,
+        replay_hash: session.replay_hash,
+    });
+}
+
+// Read the newest entry of the SlotHashes sysvar without deserializing the
+// whole (large) account: [len: u64][slot: u64][hash: 32]...
+fn most_recent_slot_hash(slot_hashes: &UncheckedAccount) -> Result<[u8; 32]> {
+    let data = slot_hashes.try_borrow_data()?;
+    require!(data.len() >= 48, ErrorCode::SlotHashUnavailable);
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&data[16..48]);
+    Ok(hash)
+}
+
+// =================== Account Types ===================
+
+#[account]
+pub struct GameSession {
+    pub version: u8,              // 1
+    pub player: Pubkey,           // 32
+    pub bump: u8,                 // 1
+    pub current_score: u64,       // 8
+    pub combo: u32,               // 4
+    pub lives: u8,                // 1
+    pub is_active: bool,          // 1
+    pub started_at: i64,          // 8
+    pub ended_at: Option<i64>,    // 1 + 8
+    pub fruits_sliced: u64,       // 8
+    pub max_combo: u32,           // 4
+    pub game_index: u64,          // 8
+    pub finalized: bool,          // 1
+    pub rng_seed: [u8; 32],       // 32
+    pub wave_number: u32,         // 4
+    pub wave_kinds: [u8; spawn::MAX_WAVE_SIZE], // 8
+    pub wave_len: u8,             // 1
+    pub wave_resolved: u8,        // 1 (bitmask of sliced/missed fruits)
+    pub fruits_missed: u32,       // 4
+    pub bombs_hit: u32,           // 4
+    pub active_powerup: Option<PowerupKind>, // 1 + 1
+    pub powerup_expires_at: i64,  // 8
+    pub powerup_ready_at: [i64; POWERUP_KIND_COUNT], // 8 * 3
+    pub last_slice_at: i64,       // 8
+    pub suspicious: bool,         // 1
+    pub slice_window_start: i64,  // 8
+    pub slices_in_window: u16,    // 2
+    pub replay_hash: [u8; 32],    // 32 (see the replay module)
+    pub leaderboard_pending: bool, // 1 (settled on the ER, not yet on the leaderboards)
+    pub profile_delegated: bool,  // 1 (delegated together with the player's profile)
+    pub reserved: [u8; SESSION_RESERVED], // room for future fields
+}
+
+// Size: use earlier value (still reasonable)
+impl GameSession {
+    pub const VERSION: u8 = 1;
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 8 + 4 + 1 + 1 + 8 + 9 + 8 + 4 + 8 + 1
+        + 32 + 4 + spawn::MAX_WAVE_SIZE + 1 + 1 + 4 + 4
+        + 2 + 8 + 8 * POWERUP_KIND_COUNT + 8 + 1 + 8 + 2 + 32 + 1 + 1 + SESSION_RESERVED;
+
+    /// Zero the per-game counters and mark the session active again
+    pub fn reset_for_new_game(&mut self, max_lives: u8, now: i64, rng_seed: [u8; 32]) {
+        self.current_score = 0;
+        self.combo = 0;
+        self.lives = max_lives;
+        self.is_active = true;
+        self.started_at = now;
+        self.ended_at = None;
+        self.fruits_sliced = 0;
+        self.max_combo = 0;
+        self.finalized = false;
+        self.rng_seed = rng_seed;
+        self.wave_number = 0;
+        self.wave_kinds = [0; spawn::MAX_WAVE_SIZE];
+        self.wave_len = 0;
+        self.wave_resolved = 0;
+        self.fruits_missed = 0;
+        self.bombs_hit = 0;
+        self.active_powerup = None;
+        self.powerup_expires_at = 0;
+        self.powerup_ready_at = [0; POWERUP_KIND_COUNT];
+        self.last_slice_at = 0;
+        self.suspicious = false;
+        self.slice_window_start = 0;
+        self.slices_in_window = 0;
+        // The replay chain starts from the game seed
+        self.replay_hash = rng_seed;
+        self.leaderboard_pending = false;
+    }
+
+    /// Combo still standing at `now`: it drops to 0 once more than
+    /// `window_ms` has passed since the last slice (0 disables decay).
+    /// Clock time has 1s resolution, so the window is effectively rounded up.
+    pub fn combo_at(&self, now: i64, window_ms: u64) -> u32 {
+        let elapsed_ms = now.saturating_sub(self.last_slice_at).max(0) as u64 * 1000;
+        if window_ms > 0 && self.last_slice_at != 0 && elapsed_ms > window_ms {
+            0
+        } else {
+            self.combo
+        }
+    }
+
+    /// Combo after a slice at `now`: one more than the combo still standing,
+    /// so a slice after the window has passed starts a fresh combo
+    pub fn next_combo(&self, now: i64, window_ms: u64) -> u32 {
+        self.combo_at(now, window_ms).saturating_add(1)
+    }
+
+    /// Fold a scoring move into the replay hash
+    pub fn record_move(&mut self, action: ReplayAction, fruit_id: u8, now: i64, points: u64) {
+        let step = replay::ReplayStep {
+            action,
+            wave_number: self.wave_number,
+            fruit_id,
+            timestamp: now,
+            points,
+        };
+        self.replay_hash = replay::fold(&self.replay_hash, &step);
+    }
+
+    /// The power-up in effect at `now`, if any
+    pub fn active_powerup_at(&self, now: i64) -> Option<PowerupKind> {
+        self.active_powerup.filter(|_| now < self.powerup_expires_at)
+    }
+
+    /// Mark a fruit of the current wave as handled and return its type id
+    pub fn take_wave_fruit(&mut self, fruit_id: u8) -> Result<u8> {
+        require!(fruit_id < self.wave_len, ErrorCode::InvalidFruit);
+        let bit = 1u8 << fruit_id;
+        require!(self.wave_resolved & bit == 0, ErrorCode::FruitAlreadyResolved);
+        self.wave_resolved |= bit;
+        Ok(self.wave_kinds[fruit_id as usize])
+    }
+}
+
+#[account]
+pub struct PlayerProfile {
+    pub version: u8,                    // 1
+    pub owner: Pubkey,                  // 32
+    pub username: Option<String>,       // 1 + 4 + 32 (max)
+    pub high_score: u64,                // 8
+    pub total_games: u64,               // 8
+    pub total_fruits_sliced: u64,       // 8
+    pub bump: u8,                       // 1
+    pub reserved: [u8; PROFILE_RESERVED], // room for future fields
+}
+
+impl PlayerProfile {
+    pub const VERSION: u8 = 1;
+    pub const LEN: usize = 8 + 1 + 32 + 37 + 8 + 8 + 8 + 1 + PROFILE_RESERVED;
+}
+
+#[account]
+pub struct GameConfig {
+    pub version: u8,                    // 1
+    pub admin: Pubkey,                  // 32
     pub max_lives: u8,                  // 1
     pub max_points_per_fruit: u64,      // 8
     pub combo_multiplier_base: u64,     // 8
-    pub leaderboard_capacity: u8,       // 1
     pub bump: u8,                       // 1
-    pub leaderboard: Vec<LeaderboardEntry>, // 4 + N * ENTRY_SIZE
+    pub active_season: u32,             // 4
+    pub season_count: u32,              // 4
+    pub bomb_penalty: BombPenalty,      // 1 + 8
+    pub powerup_durations: [u32; POWERUP_KIND_COUNT], // 4 * 3
+    pub powerup_cooldowns: [u32; POWERUP_KIND_COUNT], // 4 * 3
+    pub multi_slice_bonus: u64,         // 8
+    pub max_slice_batch: u8,            // 1
+    pub combo_window_ms: u64,           // 8
+    pub scoring_curve: ScoringCurve,    // ScoringCurve::MAX_SIZE
+    pub max_slices_per_second: u16,     // 2
+    pub max_score_per_second: u64,      // 8
+    pub min_leaderboard_duration: i64,  // 8
+    pub allowed_validators: Vec<Pubkey>, // 4 + 32 * MAX_ALLOWED_VALIDATORS
+    pub commit_frequency_ms: u32,       // 4
+    pub pending_admin: Option<Pubkey>,  // 1 + 32
+    pub role_grants: Vec<RoleGrant>,    // 4 + RoleGrant::SIZE * MAX_ROLE_GRANTS
+    pub paused: bool,                   // 1
+    pub pause_reason: u8,               // 1
+    pub reserved: [u8; CONFIG_RESERVED], // room for future fields
 }
 
-// Precomputed max size (see GAME_CONFIG_MAX_LEN)
 impl GameConfig {
-    pub const LEN: usize = GAME_CONFIG_MAX_LEN;
+    pub const VERSION: u8 = 1;
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 8 + 8 + 1 + 4 + 4 + 9 + 4 * POWERUP_KIND_COUNT * 2 + 8 + 1 + 8
+        + ScoringCurve::MAX_SIZE + 2 + 8 + 8 + 4 + 32 * MAX_ALLOWED_VALIDATORS + 4
+        + 33 + 4 + RoleGrant::SIZE * MAX_ROLE_GRANTS + 1 + 1 + CONFIG_RESERVED;
+
+    /// A fresh config: the given game rules, every other setting at its default
+    pub fn new(
+        admin: Pubkey,
+        max_lives: u8,
+        max_points_per_fruit: u64,
+        combo_multiplier_base: u64,
+        bump: u8,
+    ) -> Self {
+        GameConfig {
+            version: Self::VERSION,
+            admin,
+            max_lives,
+            max_points_per_fruit,
+            combo_multiplier_base,
+            bump,
+            active_season: NO_ACTIVE_SEASON,
+            season_count: 0,
+            bomb_penalty: BombPenalty::default(),
+            powerup_durations: DEFAULT_POWERUP_DURATIONS,
+            powerup_cooldowns: DEFAULT_POWERUP_COOLDOWNS,
+            multi_slice_bonus: DEFAULT_MULTI_SLICE_BONUS,
+            max_slice_batch: DEFAULT_MAX_SLICE_BATCH,
+            combo_window_ms: DEFAULT_COMBO_WINDOW_MS,
+            scoring_curve: ScoringCurve::default(),
+            max_slices_per_second: DEFAULT_MAX_SLICES_PER_SECOND,
+            max_score_per_second: DEFAULT_MAX_SCORE_PER_SECOND,
+            min_leaderboard_duration: DEFAULT_MIN_LEADERBOARD_DURATION,
+            allowed_validators: vec![DEFAULT_ER_VALIDATOR],
+            commit_frequency_ms: DEFAULT_COMMIT_FREQUENCY_MS,
+            pending_admin: None,
+            role_grants: Vec::new(),
+            paused: false,
+            pause_reason: PAUSE_REASON_NONE,
+            reserved: [0; CONFIG_RESERVED],
+        }
+    }
+
+    /// The admin holds every role; anyone else needs a grant carrying `role`
+    pub fn has_role(&self, authority: &Pubkey, role: u8) -> bool {
+        *authority == self.admin
+            || self
+                .role_grants
+                .iter()
+                .any(|grant| grant.authority == *authority && grant.roles & role == role)
+    }
+}
+
+// One fruit cut by a swipe (see slice_fruits)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct FruitHit {
+    pub fruit_id: u8,
+}
+
+// Per-fruit result reported by FruitsSliced
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SlicedFruit {
+    pub fruit_id: u8,
+    pub kind: u8,
+    pub points: u64,
+}
+
+// Power-ups advertised by the game. Only DoublePoints changes on-chain
+// scoring; the others are tracked so clients agree on timing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerupKind {
+    SlowMotion,
+    FreezeTime,
+    DoublePoints,
+}
+
+// What slicing a bomb costs the player
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BombPenalty {
+    LoseLives(u8),
+    LosePoints(u64),
+    EndGame,
+}
+
+impl Default for BombPenalty {
+    fn default() -> Self {
+        BombPenalty::LoseLives(1)
+    }
+}
+
+// One changed GameConfig field, reported by ConfigUpdated
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigChange {
+    MaxLives { old: u8, new: u8 },
+    MaxPointsPerFruit { old: u64, new: u64 },
+    ComboMultiplierBase { old: u64, new: u64 },
+    BombPenalty { old: BombPenalty, new: BombPenalty },
+    MultiSliceBonus { old: u64, new: u64 },
+    MaxSliceBatch { old: u8, new: u8 },
+    ComboWindowMs { old: u64, new: u64 },
+    PowerupDuration { kind: PowerupKind, old: u32, new: u32 },
+    PowerupCooldown { kind: PowerupKind, old: u32, new: u32 },
+    ScoringCurve { old: ScoringCurve, new: ScoringCurve },
+    MaxSlicesPerSecond { old: u16, new: u16 },
+    MaxScorePerSecond { old: u64, new: u64 },
+    MinLeaderboardDuration { old: i64, new: i64 },
+    AllowedValidators { old: Vec<Pubkey>, new: Vec<Pubkey> },
+    CommitFrequencyMs { old: u32, new: u32 },
+    PendingAdmin { old: Option<Pubkey>, new: Option<Pubkey> },
+    Admin { old: Pubkey, new: Pubkey },
+    Roles { authority: Pubkey, old: u8, new: u8 },
+    Paused { old: bool, new: bool },
+    PauseReason { old: u8, new: u8 },
+    SeasonCount { old: u32, new: u32 },
+    ActiveSeason { old: u32, new: u32 },
+}
+
+// Roles held by a non-admin key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoleGrant {
+    pub authority: Pubkey,              // 32
+    pub roles: u8,                      // 1
+}
+
+impl RoleGrant {
+    pub const SIZE: usize = 32 + 1;
+}
+
+// Which anti-cheat limit a session broke
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SuspicionReason {
+    SliceRate,
+    ScoreRate,
+}
+
+// Fruit kind definition (serializable)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct FruitKind {
+    pub base_points: u64,               // 8
+    pub rarity_weight: u16,             // 2
+    pub flags: u8,                      // 1
+}
+
+impl FruitKind {
+    pub const SIZE: usize = 8 + 2 + 1;
+
+    pub fn is_bomb(&self) -> bool {
+        self.flags & FRUIT_FLAG_BOMB != 0
+    }
+}
+
+#[account]
+pub struct FruitCatalogue {
+    pub bump: u8,                       // 1
+    pub kinds: Vec<FruitKind>,          // 4 + MAX_FRUIT_KINDS * SIZE
+}
+
+impl FruitCatalogue {
+    pub const LEN: usize = 8 + 1 + 4 + MAX_FRUIT_KINDS * FruitKind::SIZE;
+
+    /// Look up a kind by id
+    pub fn kind(&self, kind_id: u8) -> Result<&FruitKind> {
+        self.kinds
+            .get(kind_id as usize)
+            .ok_or_else(|| error!(ErrorCode::InvalidFruitKind))
+    }
+}
+
+#[account]
+pub struct Leaderboard {
+    pub board_id: u32,                  // 4
+    pub capacity: u16,                  // 2
+    pub bump: u8,                       // 1
+    pub entries: Vec<LeaderboardEntry>, // 4 + capacity * ENTRY_SIZE
+}
+
+impl Leaderboard {
+    pub const BASE_LEN: usize = 8 + 4 + 2 + 1 + 4;
+
+    /// Account size needed to hold `capacity` entries
+    pub fn space(capacity: usize) -> usize {
+        Self::BASE_LEN + capacity * LEADERBOARD_ENTRY_SIZE
+    }
+}
+
+#[account]
+pub struct Season {
+    pub season_id: u32,                 // 4
+    pub start_ts: i64,                  // 8
+    pub end_ts: i64,                    // 8
+    pub closed_at: Option<i64>,         // 1 + 8
+    pub bump: u8,                       // 1
+}
+
+impl Season {
+    pub const LEN: usize = 8 + 4 + 8 + 8 + 9 + 1;
+
+    /// Whether scores submitted at `now` count towards this season
+    pub fn is_open_at(&self, now: i64) -> bool {
+        self.closed_at.is_none() && now >= self.start_ts && now < self.end_ts
+    }
+}
+
+// Ban list entry: the PDA's existence is the ban
+#[account]
+pub struct PlayerBan {
+    pub player: Pubkey,                 // 32
+    pub reason: u8,                     // 1
+    pub banned_by: Pubkey,              // 32
+    pub banned_at: i64,                 // 8
+    pub bump: u8,                       // 1
+}
+
+impl PlayerBan {
+    pub const LEN: usize = 8 + 32 + 1 + 32 + 8 + 1;
+}
+
+// Frozen final standings of a closed season (never written after creation)
+#[account]
+pub struct SeasonArchive {
+    pub season_id: u32,                 // 4
+    pub start_ts: i64,                  // 8
+    pub end_ts: i64,                    // 8
+    pub closed_at: i64,                 // 8
+    pub bump: u8,                       // 1
+    pub entries: Vec<LeaderboardEntry>, // 4 + N * ENTRY_SIZE
+}
+
+impl SeasonArchive {
+    pub const BASE_LEN: usize = 8 + 4 + 8 + 8 + 8 + 1 + 4;
+
+    /// Account size needed to archive `entries` standings
+    pub fn space(entries: usize) -> usize {
+        Self::BASE_LEN + entries * LEADERBOARD_ENTRY_SIZE
+    }
+}
+
+#[account]
+pub struct SessionToken {
+    pub player: Pubkey,                 // 32
+    pub session_signer: Pubkey,         // 32
+    pub valid_until: i64,               // 8
+    pub allowed_instructions: u8,       // 1
+    pub bump: u8,                       // 1
 }
 
-// Leaderboard entry (serializable)
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
-pub struct LeaderboardEntry {
-    pub player: Pubkey,  // 32
-    pub score: u64,      // 8
-    pub timestamp: i64,  // 8
+impl SessionToken {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1 + 1;
 }
 
 // =================== Account Contexts ===================
@@ -547,6 +2384,14 @@ pub struct InitializeSession<'info> {
     )]
     pub config: Account<'info, GameConfig>,
 
+    /// CHECK: SlotHashes sysvar, used to seed the fruit RNG.
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// CHECK: Ban PDA of the player; must not exist.
+    #[account(seeds = [BAN_SEED, player.key().as_ref()], bump)]
+    pub player_ban: UncheckedAccount<'info>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -571,155 +2416,740 @@ pub struct InitializeProfile<'info> {
 }
 
 #[derive(Accounts)]
-pub struct SliceFruit<'info> {
+pub struct SliceFruit<'info> {
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    // config read-only reference to validate points and multiplier
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [CATALOGUE_SEED],
+        bump = catalogue.bump
+    )]
+    pub catalogue: Account<'info, FruitCatalogue>,
+
+    // Player or session key; checked in the handler (see require_session_authority)
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SESSION_TOKEN_SEED, session.player.as_ref(), authority.key().as_ref()],
+        bump = session_token.bump
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
+}
+
+#[derive(Accounts)]
+pub struct SpawnWave<'info> {
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    // config read-only reference for the pause switch
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [CATALOGUE_SEED],
+        bump = catalogue.bump
+    )]
+    pub catalogue: Account<'info, FruitCatalogue>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SESSION_TOKEN_SEED, session.player.as_ref(), authority.key().as_ref()],
+        bump = session_token.bump
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
+}
+
+#[derive(Accounts)]
+pub struct MissFruit<'info> {
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    // config read-only reference for the pause switch
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [CATALOGUE_SEED],
+        bump = catalogue.bump
+    )]
+    pub catalogue: Account<'info, FruitCatalogue>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SESSION_TOKEN_SEED, session.player.as_ref(), authority.key().as_ref()],
+        bump = session_token.bump
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
+}
+
+#[derive(Accounts)]
+pub struct SliceBomb<'info> {
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    // config read-only reference for the bomb penalty
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [CATALOGUE_SEED],
+        bump = catalogue.bump
+    )]
+    pub catalogue: Account<'info, FruitCatalogue>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SESSION_TOKEN_SEED, session.player.as_ref(), authority.key().as_ref()],
+        bump = session_token.bump
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
+}
+
+#[derive(Accounts)]
+pub struct ActivatePowerup<'info> {
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    // config read-only reference for power-up durations and cooldowns
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SESSION_TOKEN_SEED, session.player.as_ref(), authority.key().as_ref()],
+        bump = session_token.bump
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
+}
+
+#[derive(Accounts)]
+pub struct EndSession<'info> {
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump,
+        has_one = player
+    )]
+    pub session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, player.key().as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    // Global leaderboard lives in its own PDA so score submissions never lock the config
+    #[account(
+        mut,
+        seeds = [LEADERBOARD_SEED, &GLOBAL_LEADERBOARD_ID.to_le_bytes()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+
+    // Read-only: tells us which season (if any) is active
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [SEASON_SEED, &season.season_id.to_le_bytes()],
+        bump = season.bump
+    )]
+    pub season: Option<Account<'info, Season>>,
+
+    #[account(
+        mut,
+        seeds = [LEADERBOARD_SEED, &season_leaderboard.board_id.to_le_bytes()],
+        bump = season_leaderboard.bump
+    )]
+    pub season_leaderboard: Option<Account<'info, Leaderboard>>,
+
+    /// CHECK: Session owner, matched against `session.player` via `has_one`.
+    pub player: UncheckedAccount<'info>,
+
+    /// CHECK: Ban PDA of the player; must not exist.
+    #[account(seeds = [BAN_SEED, player.key().as_ref()], bump)]
+    pub player_ban: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SESSION_TOKEN_SEED, player.key().as_ref(), authority.key().as_ref()],
+        bump = session_token.bump
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitSettledScore<'info> {
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [LEADERBOARD_SEED, &GLOBAL_LEADERBOARD_ID.to_le_bytes()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [SEASON_SEED, &season.season_id.to_le_bytes()],
+        bump = season.bump
+    )]
+    pub season: Option<Account<'info, Season>>,
+
+    #[account(
+        mut,
+        seeds = [LEADERBOARD_SEED, &season_leaderboard.board_id.to_le_bytes()],
+        bump = season_leaderboard.bump
+    )]
+    pub season_leaderboard: Option<Account<'info, Leaderboard>>,
+
+    /// CHECK: Ban PDA of the player; must not exist.
+    #[account(seeds = [BAN_SEED, session.player.as_ref()], bump)]
+    pub player_ban: UncheckedAccount<'info>,
+}
+
+#[delegate]
+#[derive(Accounts)]
+pub struct DelegateSession<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+     #[account(
+        mut,
+        del, // 👈 this tells the SDK this is the delegatable PDA
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+}
+
+
+#[delegate]
+#[derive(Accounts)]
+pub struct DelegateSessionWithProfile<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        del,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        del,
+        seeds = [PROFILE_SEED, session.player.as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SettleSession<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    // Delegated together with the session
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, session.player.as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+
+    /// CHECK: Ban PDA of the player; must not exist.
+    #[account(seeds = [BAN_SEED, session.player.as_ref()], bump)]
+    pub player_ban: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SESSION_TOKEN_SEED, session.player.as_ref(), authority.key().as_ref()],
+        bump = session_token.bump
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
+
+    /// CHECK: Magic context account required by Ephemeral Rollups SDK.
+    /// Anchor cannot verify this account. Safety is guaranteed by the SDK.
+    #[account(mut)]
+    pub magic_context: AccountInfo<'info>,
+
+    /// CHECK: Ephemeral Rollups validator program account.
+    /// Safety is guaranteed by the SDK; used only for commit/undelegate calls.
+    pub magic_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CommitSession<'info> {
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, session.player.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, GameSession>,
+
+    /// CHECK: Magic context account required by Ephemeral Rollups SDK.
+    /// Anchor cannot verify this account. Safety is guaranteed by the SDK.
+    #[account(mut)]
+    pub magic_context: AccountInfo<'info>,
+
+    /// CHECK: Ephemeral Rollups validator program account.
+    /// Safety is guaranteed by the SDK; used only for commit/undelegate calls.
+    pub magic_program: AccountInfo<'info>,
+
+    /// Payer / authority account required by the SDK helper
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [SESSION_TOKEN_SEED, session.player.as_ref(), authority.key().as_ref()],
+        bump = session_token.bump
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
+
+    // Required to finish a session delegated with delegate_session_with_profile
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, session.player.as_ref()],
+        bump = player_profile.bump
+    )]
+    pub player_profile: Option<Account<'info, PlayerProfile>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeSessionToken<'info> {
+    #[account(
+        init,
+        payer = player,
+        space = SessionToken::LEN,
+        seeds = [SESSION_TOKEN_SEED, player.key().as_ref(), session_signer.key().as_ref()],
+        bump
+    )]
+    pub session_token: Account<'info, SessionToken>,
+
+    // The throwaway keypair co-signs to prove the client actually holds it
+    #[account(mut)]
+    pub session_signer: Signer<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSessionToken<'info> {
+    #[account(
+        mut,
+        close = player,
+        seeds = [SESSION_TOKEN_SEED, session_token.player.as_ref(), session_token.session_signer.as_ref()],
+        bump = session_token.bump,
+        has_one = player
+    )]
+    pub session_token: Account<'info, SessionToken>,
+
+    /// CHECK: Receives the token rent; matched against `session_token.player` via `has_one`.
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpSessionToken<'info> {
+    #[account(
+        seeds = [SESSION_TOKEN_SEED, player.key().as_ref(), session_signer.key().as_ref()],
+        bump = session_token.bump,
+        has_one = player,
+        has_one = session_signer
+    )]
+    pub session_token: Account<'info, SessionToken>,
+
+    /// CHECK: Plain system account receiving lamports; matched via `has_one`.
+    #[account(mut)]
+    pub session_signer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StartNewGame<'info> {
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, player.key().as_ref()],
+        bump = session.bump,
+        has_one = player
+    )]
+    pub session: Account<'info, GameSession>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    /// CHECK: SlotHashes sysvar, used to seed the fruit RNG.
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// CHECK: Ban PDA of the player; must not exist.
+    #[account(seeds = [BAN_SEED, player.key().as_ref()], bump)]
+    pub player_ban: UncheckedAccount<'info>,
+
+    pub player: Signer<'info>,
+}
+
+// InitializeConfig: admin creates the config PDA (one-time)
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(init, payer = payer, space = GameConfig::LEN, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Required when built with the `restrict-init` feature
+    pub program_data: Option<Account<'info, ProgramData>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCatalogue<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = FruitCatalogue::LEN,
+        seeds = [CATALOGUE_SEED],
+        bump
+    )]
+    pub catalogue: Account<'info, FruitCatalogue>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFruitKind<'info> {
+    #[account(
+        mut,
+        seeds = [CATALOGUE_SEED],
+        bump = catalogue.bump
+    )]
+    pub catalogue: Account<'info, FruitCatalogue>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(capacity: u16)]
+pub struct InitializeLeaderboard<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Leaderboard::space(capacity as usize),
+        seeds = [LEADERBOARD_SEED, &GLOBAL_LEADERBOARD_ID.to_le_bytes()],
+        bump
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_capacity: u16)]
+pub struct ResizeLeaderboard<'info> {
     #[account(
         mut,
-        seeds = [SESSION_SEED, session.player.as_ref()],
-        bump = session.bump
+        seeds = [LEADERBOARD_SEED, &leaderboard.board_id.to_le_bytes()],
+        bump = leaderboard.bump,
+        realloc = Leaderboard::space(new_capacity as usize),
+        realloc::payer = admin,
+        realloc::zero = false
     )]
-    pub session: Account<'info, GameSession>,
+    pub leaderboard: Account<'info, Leaderboard>,
 
-    // config read-only reference to validate points and multiplier
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump
-    )]
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
     pub config: Account<'info, GameConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct LoseLife<'info> {
+pub struct StrikeLeaderboardEntry<'info> {
     #[account(
         mut,
-        seeds = [SESSION_SEED, session.player.as_ref()],
-        bump = session.bump
+        seeds = [LEADERBOARD_SEED, &leaderboard.board_id.to_le_bytes()],
+        bump = leaderboard.bump
     )]
-    pub session: Account<'info, GameSession>,
+    pub leaderboard: Account<'info, Leaderboard>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+
+    pub moderator: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct EndSession<'info> {
-    #[account(
-        mut,
-        seeds = [SESSION_SEED, session.player.as_ref()],
-        bump = session.bump,
-        has_one = player
-    )]
-    pub session: Account<'info, GameSession>,
-
+pub struct VoidHighScore<'info> {
     #[account(
         mut,
-        seeds = [PROFILE_SEED, player.key().as_ref()],
+        seeds = [PROFILE_SEED, player_profile.owner.as_ref()],
         bump = player_profile.bump
     )]
     pub player_profile: Account<'info, PlayerProfile>,
 
-    // Require config mut so we can update leaderboard
-    #[account(
-        mut,
-        seeds = [CONFIG_SEED],
-        bump = config.bump
-    )]
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
     pub config: Account<'info, GameConfig>,
 
-    pub player: Signer<'info>,
+    pub moderator: Signer<'info>,
 }
 
-#[delegate]
 #[derive(Accounts)]
-pub struct DelegateSession<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-
-     #[account(
-        mut,
-        del, // 👈 this tells the SDK this is the delegatable PDA
-        seeds = [SESSION_SEED, session.player.as_ref()],
-        bump = session.bump
+#[instruction(player: Pubkey)]
+pub struct BanPlayer<'info> {
+    #[account(
+        init,
+        payer = moderator,
+        space = PlayerBan::LEN,
+        seeds = [BAN_SEED, player.as_ref()],
+        bump
     )]
-    pub session: Account<'info, GameSession>,
-}
+    pub player_ban: Account<'info, PlayerBan>,
 
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
 
-#[derive(Accounts)]
-pub struct UndelegateSession<'info> {
     #[account(mut)]
-    pub payer: Signer<'info>,
+    pub moderator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
 
+#[derive(Accounts)]
+pub struct UnbanPlayer<'info> {
     #[account(
         mut,
-        seeds = [SESSION_SEED, session.player.as_ref()],
-        bump = session.bump
+        close = moderator,
+        seeds = [BAN_SEED, player_ban.player.as_ref()],
+        bump = player_ban.bump
     )]
-    pub session: Account<'info, GameSession>,
+    pub player_ban: Account<'info, PlayerBan>,
 
-    /// CHECK: Magic context account required by Ephemeral Rollups SDK.
-    /// Anchor cannot verify this account. Safety is guaranteed by the SDK.
-    #[account(mut)]
-    pub magic_context: AccountInfo<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
 
-    /// CHECK: Ephemeral Rollups validator program account.
-    /// Safety is guaranteed by the SDK; used only for commit/undelegate calls.
-    pub magic_program: AccountInfo<'info>,
+    #[account(mut)]
+    pub moderator: Signer<'info>,
 }
 
-
 #[derive(Accounts)]
-pub struct CheckpointSession<'info> {
+#[instruction(season_id: u32, start_ts: i64, end_ts: i64, capacity: u16)]
+pub struct CreateSeason<'info> {
     #[account(
-        mut,
-        seeds = [SESSION_SEED, session.player.as_ref()],
-        bump = session.bump
+        init,
+        payer = admin,
+        space = Season::LEN,
+        seeds = [SEASON_SEED, &season_id.to_le_bytes()],
+        bump
     )]
-    pub session: Account<'info, GameSession>,
+    pub season: Account<'info, Season>,
 
-    /// CHECK: Magic context account required by Ephemeral Rollups SDK.
-    /// Anchor cannot verify this account. Safety is guaranteed by the SDK.
-    #[account(mut)]
-    pub magic_context: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = Leaderboard::space(capacity as usize),
+        seeds = [LEADERBOARD_SEED, &season_id.to_le_bytes()],
+        bump
+    )]
+    pub season_leaderboard: Account<'info, Leaderboard>,
 
-    /// CHECK: Ephemeral Rollups validator program account.
-    /// Safety is guaranteed by the SDK; used only for commit/checkpoint calls.
-    pub magic_program: AccountInfo<'info>,
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
 
-    /// Payer / authority account required by the SDK helper
     #[account(mut)]
-    pub payer: Signer<'info>,
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CommitSession<'info> {
+pub struct CloseSeason<'info> {
     #[account(
         mut,
-        seeds = [SESSION_SEED, session.player.as_ref()],
-        bump = session.bump
+        seeds = [SEASON_SEED, &season.season_id.to_le_bytes()],
+        bump = season.bump
     )]
-    pub session: Account<'info, GameSession>,
+    pub season: Account<'info, Season>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [LEADERBOARD_SEED, &season.season_id.to_le_bytes()],
+        bump = season_leaderboard.bump
+    )]
+    pub season_leaderboard: Account<'info, Leaderboard>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = SeasonArchive::space(season_leaderboard.entries.len()),
+        seeds = [SEASON_ARCHIVE_SEED, &season.season_id.to_le_bytes()],
+        bump
+    )]
+    pub archive: Account<'info, SeasonArchive>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
 
-    /// CHECK: Magic context account required by Ephemeral Rollups SDK.
-    /// Anchor cannot verify this account. Safety is guaranteed by the SDK.
     #[account(mut)]
-    pub magic_context: AccountInfo<'info>,
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// CHECK: Ephemeral Rollups validator program account.
-    /// Safety is guaranteed by the SDK; used only for commit calls.
-    pub magic_program: AccountInfo<'info>,
+#[derive(Accounts)]
+#[instruction(player: Pubkey)]
+pub struct MigrateSession<'info> {
+    /// CHECK: Legacy GameSession, decoded and rewritten by the handler.
+    #[account(mut, owner = crate::ID, seeds = [SESSION_SEED, player.as_ref()], bump)]
+    pub session: UncheckedAccount<'info>,
+
+    /// CHECK: SlotHashes sysvar, seeds a game still in progress.
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
 
-    /// Payer / authority account required by the SDK helper
     #[account(mut)]
     pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
-
-// InitializeConfig: admin creates the config PDA (one-time)
 #[derive(Accounts)]
-pub struct InitializeConfig<'info> {
-    #[account(init, payer = payer, space = GameConfig::LEN, seeds = [CONFIG_SEED], bump)]
-    pub config: Account<'info, GameConfig>,
+#[instruction(player: Pubkey)]
+pub struct MigrateProfile<'info> {
+    /// CHECK: Legacy PlayerProfile, decoded and rewritten by the handler.
+    #[account(mut, owner = crate::ID, seeds = [PROFILE_SEED, player.as_ref()], bump)]
+    pub player_profile: UncheckedAccount<'info>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: Legacy GameConfig, decoded and rewritten by the handler; the
+    /// handler checks the legacy admin.
+    #[account(mut, owner = crate::ID, seeds = [CONFIG_SEED], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Leaderboard::space(LEGACY_LEADERBOARD_CAPACITY),
+        seeds = [LEADERBOARD_SEED, &GLOBAL_LEADERBOARD_ID.to_le_bytes()],
+        bump
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
 
+    #[account(mut)]
+    pub admin: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
@@ -733,17 +3163,27 @@ pub struct UpdateConfig<'info> {
     pub admin: Signer<'info>,
 }
 
+/// Session data returned by get_session
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct SessionData {
     pub player: Pubkey,              // 32
     pub current_score: u64,          // 8
-    pub combo: u8,                   // 1
+    pub combo: u32,                  // 4
     pub lives: u8,                   // 1
     pub is_active: bool,             // 1
     pub started_at: i64,             // 8
     pub ended_at: Option<i64>,       // 9
     pub fruits_sliced: u64,          // 8
-    pub max_combo: u8,               // 1
+    pub max_combo: u32,              // 4
+    pub game_index: u64,             // 8
+    pub finalized: bool,             // 1
+    pub wave_number: u32,            // 4
+    pub active_powerup: Option<PowerupKind>, // 2
+    pub powerup_expires_at: i64,     // 8
+    pub suspicious: bool,            // 1
+    pub replay_hash: [u8; 32],       // 32
+    pub leaderboard_pending: bool,   // 1
+    pub profile_delegated: bool,     // 1
 }
 
 /// Player profile data returned by get_profile
@@ -763,17 +3203,33 @@ pub struct ConfigData {
     pub max_lives: u8,                  // 1
     pub max_points_per_fruit: u64,      // 8
     pub combo_multiplier_base: u64,     // 8
-    pub leaderboard_capacity: u8,       // 1
+    pub active_season: u32,             // 4
+    pub bomb_penalty: BombPenalty,      // 9
+    pub multi_slice_bonus: u64,         // 8
+    pub max_slice_batch: u8,            // 1
+    pub combo_window_ms: u64,           // 8
+    pub scoring_curve: ScoringCurve,    // up to ScoringCurve::MAX_SIZE
+    pub max_slices_per_second: u16,     // 2
+    pub max_score_per_second: u64,      // 8
+    pub min_leaderboard_duration: i64,  // 8
+    pub allowed_validators: Vec<Pubkey>, // up to 4 + 32 * MAX_ALLOWED_VALIDATORS
+    pub commit_frequency_ms: u32,       // 4
+    pub pending_admin: Option<Pubkey>,  // 33
+    pub role_grants: Vec<RoleGrant>,    // up to 4 + RoleGrant::SIZE * MAX_ROLE_GRANTS
+    pub paused: bool,                   // 1
+    pub pause_reason: u8,               // 1
 }
 
 /// Focused session statistics returned by get_session_stats
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct SessionStats {
     pub current_score: u64,             // 8
-    pub combo: u8,                      // 1
-    pub max_combo: u8,                  // 1
+    pub combo: u32,                     // 4
+    pub max_combo: u32,                 // 4
     pub fruits_sliced: u64,             // 8
     pub lives: u8,                      // 1
+    pub fruits_missed: u32,             // 4
+    pub bombs_hit: u32,                 // 4
 }
 
 #[derive(Accounts)]
@@ -797,10 +3253,19 @@ pub struct GetProfile<'info> {
 #[derive(Accounts)]
 pub struct GetLeaderboard<'info> {
     #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump
+        seeds = [LEADERBOARD_SEED, &leaderboard.board_id.to_le_bytes()],
+        bump = leaderboard.bump
     )]
-    pub config: Account<'info, GameConfig>,
+    pub leaderboard: Account<'info, Leaderboard>,
+}
+
+#[derive(Accounts)]
+pub struct GetSeasonArchive<'info> {
+    #[account(
+        seeds = [SEASON_ARCHIVE_SEED, &archive.season_id.to_le_bytes()],
+        bump = archive.bump
+    )]
+    pub archive: Account<'info, SeasonArchive>,
 }
 
 #[derive(Accounts)]
@@ -816,6 +3281,21 @@ pub struct CalculatePoints<'info> {
         bump = config.bump
     )]
     pub config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [CATALOGUE_SEED],
+        bump = catalogue.bump
+    )]
+    pub catalogue: Account<'info, FruitCatalogue>,
+}
+
+#[derive(Accounts)]
+pub struct GetFruitCatalogue<'info> {
+    #[account(
+        seeds = [CATALOGUE_SEED],
+        bump = catalogue.bump
+    )]
+    pub catalogue: Account<'info, FruitCatalogue>,
 }
 
 #[derive(Accounts)]
@@ -853,40 +3333,303 @@ pub enum ErrorCode {
 
     #[msg("Unauthorized")]
     Unauthorized,
+
+    #[msg("Session token does not belong to this player and signer")]
+    InvalidSessionToken,
+
+    #[msg("Session token has expired")]
+    SessionTokenExpired,
+
+    #[msg("Session token does not allow this instruction")]
+    SessionTokenInstructionNotAllowed,
+
+    #[msg("Session token expiry must be in the future and within the max validity")]
+    InvalidSessionTokenExpiry,
+
+    #[msg("Session token permissions are empty or unknown")]
+    InvalidSessionTokenPermissions,
+
+    #[msg("Top-up amount must be greater than zero")]
+    InvalidTopUpAmount,
+
+    #[msg("Session is still active")]
+    SessionStillActive,
+
+    #[msg("Previous game has not been finalized with end_session")]
+    SessionNotFinalized,
+
+    #[msg("Leaderboard can grow by at most 10KB per instruction")]
+    LeaderboardGrowthTooLarge,
+
+    #[msg("Season does not match the active season")]
+    InvalidSeason,
+
+    #[msg("Season end must be after its start")]
+    InvalidSeasonWindow,
+
+    #[msg("Close the active season before creating a new one")]
+    SeasonStillActive,
+
+    #[msg("SlotHashes sysvar is empty")]
+    SlotHashUnavailable,
+
+    #[msg("Fruit id is not part of the current wave")]
+    InvalidFruit,
+
+    #[msg("Fruit was already sliced or missed")]
+    FruitAlreadyResolved,
+
+    #[msg("Unknown fruit kind or invalid kind flags")]
+    InvalidFruitKind,
+
+    #[msg("Fruit catalogue has no spawnable kinds")]
+    EmptyFruitCatalogue,
+
+    #[msg("Bombs cannot be sliced for points or missed")]
+    FruitIsBomb,
+
+    #[msg("Fruit is not a bomb")]
+    NotABomb,
+
+    #[msg("Another power-up is still active")]
+    PowerupAlreadyActive,
+
+    #[msg("Power-up is on cooldown")]
+    PowerupOnCooldown,
+
+    #[msg("Power-up duration must be positive and not exceed its cooldown")]
+    InvalidPowerupConfig,
+
+    #[msg("Slice batch is empty or larger than the configured maximum")]
+    InvalidSliceBatch,
+
+    #[msg("Scoring curve tiers must be non-empty, ascending and bounded; exponential caps must be positive")]
+    InvalidScoringCurve,
+
+    #[msg("Linear scoring step must not exceed MAX_CURVE_STEP")]
+    CurveStepTooLarge,
+
+    #[msg("Stepped scoring bonuses must not exceed MAX_CURVE_BONUS")]
+    CurveBonusTooLarge,
+
+    #[msg("Exponential scoring cap must not exceed MAX_CURVE_CAP")]
+    CurveCapTooLarge,
+
+    #[msg("Multi-slice bonus must not exceed MAX_MULTI_SLICE_BONUS")]
+    MultiSliceBonusTooLarge,
+
+    #[msg("Minimum leaderboard duration cannot be negative")]
+    InvalidAntiCheatLimits,
+
+    #[msg("Validator list must be non-empty, bounded and free of duplicates; commit frequency must be positive")]
+    InvalidDelegationConfig,
+
+    #[msg("Unknown role bits, or no room for another role grant")]
+    InvalidRoles,
+
+    #[msg("Max lives must be between 1 and MAX_LIVES_LIMIT")]
+    InvalidMaxLives,
+
+    #[msg("Max points per fruit must be between 1 and MAX_POINTS_PER_FRUIT_LIMIT")]
+    InvalidMaxPointsPerFruit,
+
+    #[msg("Combo multiplier base must be between 1 and MAX_COMBO_MULTIPLIER_BASE")]
+    InvalidComboMultiplierBase,
+
+    #[msg("A bomb penalty must cost at least one life")]
+    InvalidBombPenalty,
+
+    #[msg("The game is paused for maintenance")]
+    GamePaused,
+
+    #[msg("Pause reason must be a non-zero code")]
+    InvalidPauseReason,
+
+    #[msg("Player is banned")]
+    PlayerBanned,
+
+    #[msg("Player is not on this leaderboard")]
+    PlayerNotOnLeaderboard,
+
+    #[msg("Account is already at the current version")]
+    AccountAlreadyMigrated,
+
+    #[msg("Account data matches neither the current nor the legacy layout")]
+    UnknownAccountLayout,
+
+    #[msg("The active season and its leaderboard must be passed while a season is running")]
+    SeasonAccountsRequired,
+
+    #[msg("Session has no settled score waiting for the leaderboards")]
+    NoPendingScore,
+
+    #[msg("The settled score must be submitted with submit_settled_score first")]
+    SettledScorePending,
+
+    #[msg("Session was delegated with its profile: pass the profile to undelegate both, or use settle_session")]
+    DelegatedProfileRequired,
 }
 
 #[event]
 pub struct SessionStarted {
     pub player: Pubkey,
+    pub game_index: u64,
+    pub rng_seed: [u8; 32],
     pub timestamp: i64,
 }
 
 #[event]
 pub struct FruitSliced {
     pub player: Pubkey,
+    pub wave_number: u32,
+    pub fruit_id: u8,
+    pub kind: u8,
     pub points: u64,
-    pub combo: u8,
+    pub combo: u32,
+    pub total_score: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WaveSpawned {
+    pub player: Pubkey,
+    pub wave_number: u32,
+    pub kinds: Vec<u8>,
+    pub slot: u64,
+}
+
+#[event]
+pub struct FruitsSliced {
+    pub player: Pubkey,
+    pub wave_number: u32,
+    pub fruits: Vec<SlicedFruit>,
+    pub bonus: u64,
+    pub combo: u32,
+    pub total_score: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FruitMissed {
+    pub player: Pubkey,
+    pub wave_number: u32,
+    pub fruit_id: u8,
+    pub kind: u8,
+    pub lives: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BombHit {
+    pub player: Pubkey,
+    pub wave_number: u32,
+    pub fruit_id: u8,
+    pub kind: u8,
+    pub penalty: BombPenalty,
+    pub points_lost: u64,
+    pub lives: u8,
     pub total_score: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PowerupActivated {
+    pub player: Pubkey,
+    pub kind: PowerupKind,
+    pub expires_at: i64,
 }
 
 #[event]
 pub struct SessionCheckpoint {
     pub player: Pubkey,
     pub current_score: u64,
-    pub combo: u8,
+    pub combo: u32,
     pub fruits_sliced: u64,
+    pub replay_hash: [u8; 32],
 }
 
 #[event]
 pub struct GameOver {
     pub player: Pubkey,
     pub final_score: u64,
-    pub max_combo: u8,
+    pub max_combo: u32,
     pub fruits_sliced: u64,
     pub duration: i64,
+    pub replay_hash: [u8; 32],
+}
+
+#[event]
+pub struct SuspiciousSession {
+    pub player: Pubkey,
+    pub game_index: u64,
+    pub reason: SuspicionReason,
+    pub score: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub max_lives: u8,
+    pub max_points_per_fruit: u64,
+    pub combo_multiplier_base: u64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub authority: Pubkey,
+    pub change: ConfigChange,
+}
+
+#[event]
+pub struct LeaderboardEntryStruck {
+    pub moderator: Pubkey,
+    pub board_id: u32,
+    pub player: Pubkey,
+    pub score: u64,
+    pub reason: u8,
+}
+
+#[event]
+pub struct HighScoreVoided {
+    pub moderator: Pubkey,
+    pub player: Pubkey,
+    pub high_score: u64,
+    pub reason: u8,
 }
 
+#[event]
+pub struct PlayerBanned {
+    pub moderator: Pubkey,
+    pub player: Pubkey,
+    pub reason: u8,
+    pub timestamp: i64,
+}
 
+#[event]
+pub struct PlayerUnbanned {
+    pub moderator: Pubkey,
+    pub player: Pubkey,
+    pub reason: u8,
+    pub timestamp: i64,
+}
 
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+}
 
+#[event]
+pub struct SeasonCreated {
+    pub season_id: u32,
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
 
+#[event]
+pub struct SeasonClosed {
+    pub season_id: u32,
+    pub entries: u16,
+    pub closed_at: i64,
+}
@@ -0,0 +1,405 @@
+//! Account versioning and in-place upgrades of pre-versioning accounts.
+//!
+//! `GameSession`, `PlayerProfile` and `GameConfig` start with a `version`
+//! byte and end with zeroed reserved padding. Accounts written before
+//! versioning (version 0) have no version byte, so they are recognised by
+//! their exact legacy size. The `migrate_*` instructions decode them with the
+//! layouts below, realloc them to the current size and write the upgrade.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::{
+    set_field, spawn, ConfigChange, ErrorCode, GameConfig, GameSession, LeaderboardEntry, PlayerProfile,
+    LEADERBOARD_ENTRY_SIZE, MAX_COMBO_MULTIPLIER_BASE, MAX_LIVES_LIMIT, MAX_POINTS_PER_FRUIT_LIMIT,
+    POWERUP_KIND_COUNT, PROFILE_RESERVED, SESSION_RESERVED,
+};
+
+/// Leaderboard slots the legacy config reserved for its embedded board
+pub const LEGACY_LEADERBOARD_CAPACITY: usize = 20;
+
+/// GameSession as written before versioning
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LegacyGameSession {
+    pub player: Pubkey,
+    pub bump: u8,
+    pub current_score: u64,
+    pub combo: u8,
+    pub lives: u8,
+    pub is_active: bool,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub fruits_sliced: u64,
+    pub max_combo: u8,
+}
+
+impl LegacyGameSession {
+    pub const LEN: usize = 8 + 32 + 1 + 8 + 1 + 1 + 1 + 8 + 9 + 8 + 1;
+}
+
+/// PlayerProfile as written before versioning
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LegacyPlayerProfile {
+    pub owner: Pubkey,
+    pub username: Option<String>,
+    pub high_score: u64,
+    pub total_games: u64,
+    pub total_fruits_sliced: u64,
+    pub bump: u8,
+}
+
+impl LegacyPlayerProfile {
+    pub const LEN: usize = 8 + 32 + 37 + 8 + 8 + 8 + 1;
+}
+
+/// GameConfig as written before versioning, leaderboard embedded
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LegacyGameConfig {
+    pub admin: Pubkey,
+    pub max_lives: u8,
+    pub max_points_per_fruit: u64,
+    pub combo_multiplier_base: u64,
+    pub leaderboard_capacity: u8,
+    pub bump: u8,
+    pub leaderboard: Vec<LeaderboardEntry>,
+}
+
+impl LegacyGameConfig {
+    pub const LEN: usize = 8 + 32 + 1 + 8 + 8 + 1 + 1 + 4
+        + LEGACY_LEADERBOARD_CAPACITY * LEADERBOARD_ENTRY_SIZE + 8;
+}
+
+/// Decode a version 0 account. The discriminator is unchanged across
+/// versions; the size tells a legacy account from a current one.
+pub fn read_legacy<T: AnchorDeserialize>(
+    data: &[u8],
+    discriminator: &[u8],
+    legacy_len: usize,
+    current_len: usize,
+) -> Result<T> {
+    require!(
+        data.len() >= 8 && &data[..8] == discriminator,
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    require!(data.len() != current_len, ErrorCode::AccountAlreadyMigrated);
+    require!(data.len() == legacy_len, ErrorCode::UnknownAccountLayout);
+    // Legacy accounts are padded to their max size, so trailing bytes are expected
+    T::deserialize(&mut &data[8..])
+        .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+}
+
+/// Carry a legacy session over. A game still in progress keeps going on
+/// `rng_seed`. The legacy layout does not record whether a game was settled,
+/// so no upgraded session is finalized: an ended game must still go through
+/// end_session before the player can start the next one.
+pub fn upgrade_session(legacy: LegacyGameSession, rng_seed: [u8; 32]) -> GameSession {
+    GameSession {
+        version: GameSession::VERSION,
+        player: legacy.player,
+        bump: legacy.bump,
+        current_score: legacy.current_score,
+        combo: legacy.combo as u32,
+        lives: legacy.lives,
+        is_active: legacy.is_active,
+        started_at: legacy.started_at,
+        ended_at: legacy.ended_at,
+        fruits_sliced: legacy.fruits_sliced,
+        max_combo: legacy.max_combo as u32,
+        game_index: 0,
+        finalized: false,
+        rng_seed,
+        wave_number: 0,
+        wave_kinds: [0; spawn::MAX_WAVE_SIZE],
+        wave_len: 0,
+        wave_resolved: 0,
+        fruits_missed: 0,
+        bombs_hit: 0,
+        active_powerup: None,
+        powerup_expires_at: 0,
+        powerup_ready_at: [0; POWERUP_KIND_COUNT],
+        last_slice_at: 0,
+        suspicious: false,
+        slice_window_start: 0,
+        slices_in_window: 0,
+        replay_hash: rng_seed,
+        leaderboard_pending: false,
+        profile_delegated: false,
+        reserved: [0; SESSION_RESERVED],
+    }
+}
+
+/// Carry a legacy profile over; its layout only gains the version and padding
+pub fn upgrade_profile(legacy: LegacyPlayerProfile) -> PlayerProfile {
+    PlayerProfile {
+        version: PlayerProfile::VERSION,
+        owner: legacy.owner,
+        username: legacy.username,
+        high_score: legacy.high_score,
+        total_games: legacy.total_games,
+        total_fruits_sliced: legacy.total_fruits_sliced,
+        bump: legacy.bump,
+        reserved: [0; PROFILE_RESERVED],
+    }
+}
+
+/// Carry the legacy game rules over with every newer setting at its default.
+/// The embedded leaderboard is returned alongside, best first, for the
+/// global leaderboard PDA.
+pub fn upgrade_config(legacy: LegacyGameConfig) -> (GameConfig, Vec<LeaderboardEntry>) {
+    let config = GameConfig::new(
+        legacy.admin,
+        legacy.max_lives,
+        legacy.max_points_per_fruit,
+        legacy.combo_multiplier_base,
+        legacy.bump,
+    );
+    let capacity = legacy.leaderboard_capacity as usize;
+    let mut entries = Vec::with_capacity(legacy.leaderboard.len());
+    for entry in legacy.leaderboard {
+        crate::leaderboard::submit(&mut entries, entry, capacity);
+    }
+    (config, entries)
+}
+
+/// Pull the upgraded game rules into the bounds update_config enforces. The
+/// legacy program accepted any value, and a config it cannot migrate would
+/// leave every instruction failing, so out-of-bounds rules are clamped and
+/// each clamped field emits ConfigUpdated.
+pub fn clamp_game_rules(config: &mut GameConfig, authority: Pubkey) {
+    let max_lives = config.max_lives.clamp(1, MAX_LIVES_LIMIT);
+    set_field(&mut config.max_lives, max_lives, authority, |old, new| ConfigChange::MaxLives { old, new });
+    let max_points = config.max_points_per_fruit.clamp(1, MAX_POINTS_PER_FRUIT_LIMIT);
+    set_field(&mut config.max_points_per_fruit, max_points, authority, |old, new| {
+        ConfigChange::MaxPointsPerFruit { old, new }
+    });
+    let combo_base = config.combo_multiplier_base.clamp(1, MAX_COMBO_MULTIPLIER_BASE);
+    set_field(&mut config.combo_multiplier_base, combo_base, authority, |old, new| {
+        ConfigChange::ComboMultiplierBase { old, new }
+    });
+}
+
+/// Resize `account` to `len`, topping its rent up from `payer` or refunding
+/// the excess to it, then write `value` (discriminator included).
+pub fn store<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    value: &T,
+    len: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(len);
+    let lamports = account.lamports();
+    if rent > lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent - lamports,
+        )?;
+    } else if lamports > rent {
+        **account.try_borrow_mut_lamports()? -= lamports - rent;
+        **payer.try_borrow_mut_lamports()? += lamports - rent;
+    }
+
+    account.realloc(len, true)?;
+    let mut data = account.try_borrow_mut_data()?;
+    value.try_serialize(&mut &mut data[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Old-layout fixtures are written field by field, not through the
+    // Legacy* structs, so the decoder is checked against the real bytes.
+
+    fn fixture(discriminator: &[u8], fields: &[&[u8]], len: usize) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        for field in fields {
+            data.extend_from_slice(field);
+        }
+        assert!(data.len() <= len);
+        data.resize(len, 0);
+        data
+    }
+
+    fn legacy_session(player: &Pubkey, is_active: bool) -> Vec<u8> {
+        let ended_at: &[u8] = if is_active { &[0] } else { &[1, 0xa0, 0x86, 1, 0, 0, 0, 0, 0] };
+        fixture(
+            GameSession::DISCRIMINATOR,
+            &[
+                player.as_ref(),
+                &[254],                     // bump
+                &1_250u64.to_le_bytes(),    // current_score
+                &[7],                       // combo
+                &[2],                       // lives
+                &[is_active as u8],
+                &99_000i64.to_le_bytes(),   // started_at
+                ended_at,                   // Some(100_000) when ended
+                &42u64.to_le_bytes(),       // fruits_sliced
+                &[200],                     // max_combo
+            ],
+            LegacyGameSession::LEN,
+        )
+    }
+
+    #[test]
+    fn legacy_session_fixture_upgrades() {
+        let player = Pubkey::new_unique();
+        let data = legacy_session(&player, true);
+        let legacy: LegacyGameSession =
+            read_legacy(&data, GameSession::DISCRIMINATOR, LegacyGameSession::LEN, GameSession::LEN).unwrap();
+        assert_eq!(legacy.player, player);
+        assert_eq!(legacy.current_score, 1_250);
+        assert_eq!(legacy.ended_at, None);
+
+        let seed = [3u8; 32];
+        let session = upgrade_session(legacy, seed);
+        assert_eq!(session.version, GameSession::VERSION);
+        assert_eq!((session.bump, session.lives, session.started_at), (254, 2, 99_000));
+        assert_eq!((session.combo, session.max_combo, session.fruits_sliced), (7, 200, 42));
+        assert!(session.is_active && !session.finalized);
+        assert_eq!(session.rng_seed, seed);
+        assert_eq!(session.replay_hash, seed);
+        assert_eq!(session.reserved, [0; SESSION_RESERVED]);
+
+        let mut bytes = Vec::new();
+        session.try_serialize(&mut bytes).unwrap();
+        assert!(bytes.len() <= GameSession::LEN);
+    }
+
+    #[test]
+    fn ended_legacy_session_is_not_finalized() {
+        let data = legacy_session(&Pubkey::new_unique(), false);
+        let legacy: LegacyGameSession =
+            read_legacy(&data, GameSession::DISCRIMINATOR, LegacyGameSession::LEN, GameSession::LEN).unwrap();
+        assert_eq!(legacy.ended_at, Some(100_000));
+
+        let session = upgrade_session(legacy, [0; 32]);
+        assert!(!session.is_active && !session.finalized);
+    }
+
+    #[test]
+    fn legacy_profile_fixture_upgrades() {
+        let owner = Pubkey::new_unique();
+        let data = fixture(
+            PlayerProfile::DISCRIMINATOR,
+            &[
+                owner.as_ref(),
+                &[1],                       // Some(username)
+                &5u32.to_le_bytes(),
+                b"ninja",
+                &9_001u64.to_le_bytes(),    // high_score
+                &12u64.to_le_bytes(),       // total_games
+                &340u64.to_le_bytes(),      // total_fruits_sliced
+                &[253],                     // bump
+            ],
+            LegacyPlayerProfile::LEN,
+        );
+        let legacy: LegacyPlayerProfile =
+            read_legacy(&data, PlayerProfile::DISCRIMINATOR, LegacyPlayerProfile::LEN, PlayerProfile::LEN).unwrap();
+
+        let profile = upgrade_profile(legacy);
+        assert_eq!(profile.version, PlayerProfile::VERSION);
+        assert_eq!(profile.owner, owner);
+        assert_eq!(profile.username.as_deref(), Some("ninja"));
+        assert_eq!((profile.high_score, profile.total_games, profile.total_fruits_sliced), (9_001, 12, 340));
+        assert_eq!(profile.bump, 253);
+    }
+
+    #[test]
+    fn legacy_config_fixture_upgrades_with_defaults() {
+        let admin = Pubkey::new_unique();
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let data = fixture(
+            GameConfig::DISCRIMINATOR,
+            &[
+                admin.as_ref(),
+                &[3],                       // max_lives
+                &100u64.to_le_bytes(),      // max_points_per_fruit
+                &10u64.to_le_bytes(),       // combo_multiplier_base
+                &[10],                      // leaderboard_capacity
+                &[252],                     // bump
+                &2u32.to_le_bytes(),        // leaderboard entries
+                first.as_ref(),
+                &500u64.to_le_bytes(),
+                &10i64.to_le_bytes(),
+                second.as_ref(),
+                &800u64.to_le_bytes(),
+                &20i64.to_le_bytes(),
+            ],
+            LegacyGameConfig::LEN,
+        );
+        let legacy: LegacyGameConfig =
+            read_legacy(&data, GameConfig::DISCRIMINATOR, LegacyGameConfig::LEN, GameConfig::LEN).unwrap();
+        assert_eq!(legacy.leaderboard_capacity, 10);
+
+        let (config, entries) = upgrade_config(legacy);
+        assert_eq!(config.version, GameConfig::VERSION);
+        assert_eq!(config.admin, admin);
+        assert_eq!((config.max_lives, config.max_points_per_fruit, config.combo_multiplier_base), (3, 100, 10));
+        assert_eq!(config.bump, 252);
+        assert_eq!(config.allowed_validators, vec![crate::DEFAULT_ER_VALIDATOR]);
+        assert!(!config.paused);
+
+        // The legacy board is re-ranked best first
+        let players: Vec<Pubkey> = entries.iter().map(|e| e.player).collect();
+        assert_eq!(players, vec![second, first]);
+
+        let mut bytes = Vec::new();
+        config.try_serialize(&mut bytes).unwrap();
+        assert!(bytes.len() <= GameConfig::LEN);
+    }
+
+    #[test]
+    fn out_of_bounds_legacy_rules_are_clamped() {
+        let admin = Pubkey::new_unique();
+        let data = fixture(
+            GameConfig::DISCRIMINATOR,
+            &[
+                admin.as_ref(),
+                &[0],                       // max_lives
+                &u64::MAX.to_le_bytes(),    // max_points_per_fruit
+                &1_000u64.to_le_bytes(),    // combo_multiplier_base
+                &[10],                      // leaderboard_capacity
+                &[252],                     // bump
+                &0u32.to_le_bytes(),        // leaderboard entries
+            ],
+            LegacyGameConfig::LEN,
+        );
+        let legacy: LegacyGameConfig =
+            read_legacy(&data, GameConfig::DISCRIMINATOR, LegacyGameConfig::LEN, GameConfig::LEN).unwrap();
+
+        let (mut config, _) = upgrade_config(legacy);
+        assert!(crate::validate_game_rules(config.max_lives, config.max_points_per_fruit, config.combo_multiplier_base).is_err());
+
+        clamp_game_rules(&mut config, admin);
+        assert_eq!(
+            (config.max_lives, config.max_points_per_fruit, config.combo_multiplier_base),
+            (1, MAX_POINTS_PER_FRUIT_LIMIT, MAX_COMBO_MULTIPLIER_BASE)
+        );
+        assert!(crate::validate_game_rules(config.max_lives, config.max_points_per_fruit, config.combo_multiplier_base).is_ok());
+    }
+
+    #[test]
+    fn rejects_current_and_unknown_layouts() {
+        let legacy = legacy_session(&Pubkey::new_unique(), true);
+
+        let mut current = vec![0u8; GameSession::LEN];
+        current[..8].copy_from_slice(GameSession::DISCRIMINATOR);
+        let read = |data: &[u8]| {
+            read_legacy::<LegacyGameSession>(data, GameSession::DISCRIMINATOR, LegacyGameSession::LEN, GameSession::LEN)
+                .unwrap_err()
+        };
+        assert_eq!(read(&current), ErrorCode::AccountAlreadyMigrated.into());
+        assert_eq!(read(&legacy[..LegacyGameSession::LEN - 1]), ErrorCode::UnknownAccountLayout.into());
+
+        let mut profile = legacy.clone();
+        profile[..8].copy_from_slice(PlayerProfile::DISCRIMINATOR);
+        assert_eq!(read(&profile), anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+}
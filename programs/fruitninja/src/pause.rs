@@ -0,0 +1,223 @@
+//! The emergency pause switch.
+//!
+//! While `GameConfig::paused` is set, every instruction that starts a game,
+//! advances one or delegates a session fails with `GamePaused`. The check
+//! runs before anything else in those handlers, so a paused game changes no
+//! state. Settling, committing and admin instructions stay available.
+
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, GameConfig};
+
+/// Fail with `GamePaused` while the admin has paused the game
+pub fn require_not_paused(config: &GameConfig) -> Result<()> {
+    if config.paused {
+        msg!("Game is paused (reason {})", config.pause_reason);
+        return err!(ErrorCode::GamePaused);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fruitninja;
+    use crate::test_utils::*;
+    use crate::*;
+
+    // Handlers are called directly, so account constraints are not run;
+    // each gated handler must reject the paused config before touching
+    // anything else (including the Clock sysvar, which has no test stub).
+
+    fn paused_config() -> Account<'static, GameConfig> {
+        let mut config = GameConfig::new(Pubkey::new_unique(), 3, 100, 2, 255);
+        config.paused = true;
+        config.pause_reason = 7;
+        program_account(&config)
+    }
+
+    fn assert_paused(result: Result<()>) {
+        assert_eq!(result.unwrap_err(), ErrorCode::GamePaused.into());
+    }
+
+    #[test]
+    fn unpaused_config_passes() {
+        let config = GameConfig::new(Pubkey::new_unique(), 3, 100, 2, 255);
+        assert!(require_not_paused(&config).is_ok());
+    }
+
+    #[test]
+    fn initialize_session_is_paused() {
+        let player = Pubkey::new_unique();
+        let mut accounts = InitializeSession {
+            session: session(player, false),
+            player: signer(player),
+            config: paused_config(),
+            slot_hashes: UncheckedAccount::try_from(unchecked()),
+            player_ban: UncheckedAccount::try_from(unchecked()),
+            payer: signer(player),
+            system_program: system(),
+        };
+        assert_paused(fruitninja::initialize_session(Context::new(
+            &crate::ID,
+            &mut accounts,
+            &[],
+            InitializeSessionBumps::default(),
+        )));
+    }
+
+    #[test]
+    fn start_new_game_is_paused() {
+        let player = Pubkey::new_unique();
+        let mut accounts = StartNewGame {
+            session: session(player, false),
+            config: paused_config(),
+            slot_hashes: UncheckedAccount::try_from(unchecked()),
+            player_ban: UncheckedAccount::try_from(unchecked()),
+            player: signer(player),
+        };
+        assert_paused(fruitninja::start_new_game(Context::new(
+            &crate::ID,
+            &mut accounts,
+            &[],
+            StartNewGameBumps::default(),
+        )));
+    }
+
+    #[test]
+    fn spawn_wave_is_paused() {
+        let player = Pubkey::new_unique();
+        let mut accounts = SpawnWave {
+            session: session(player, true),
+            config: paused_config(),
+            catalogue: catalogue(),
+            authority: signer(player),
+            session_token: None,
+        };
+        assert_paused(fruitninja::spawn_wave(Context::new(
+            &crate::ID,
+            &mut accounts,
+            &[],
+            SpawnWaveBumps::default(),
+        )));
+    }
+
+    fn slice_accounts(player: Pubkey) -> SliceFruit<'static> {
+        SliceFruit {
+            session: session(player, true),
+            config: paused_config(),
+            catalogue: catalogue(),
+            authority: signer(player),
+            session_token: None,
+        }
+    }
+
+    #[test]
+    fn slice_fruit_is_paused() {
+        let mut accounts = slice_accounts(Pubkey::new_unique());
+        assert_paused(fruitninja::slice_fruit(
+            Context::new(&crate::ID, &mut accounts, &[], SliceFruitBumps::default()),
+            0,
+        ));
+    }
+
+    #[test]
+    fn slice_fruits_is_paused() {
+        let mut accounts = slice_accounts(Pubkey::new_unique());
+        assert_paused(fruitninja::slice_fruits(
+            Context::new(&crate::ID, &mut accounts, &[], SliceFruitBumps::default()),
+            vec![],
+        ));
+    }
+
+    #[test]
+    fn miss_fruit_is_paused() {
+        let player = Pubkey::new_unique();
+        let mut accounts = MissFruit {
+            session: session(player, true),
+            config: paused_config(),
+            catalogue: catalogue(),
+            authority: signer(player),
+            session_token: None,
+        };
+        assert_paused(fruitninja::miss_fruit(
+            Context::new(&crate::ID, &mut accounts, &[], MissFruitBumps::default()),
+            0,
+        ));
+    }
+
+    #[test]
+    fn slice_bomb_is_paused() {
+        let player = Pubkey::new_unique();
+        let mut accounts = SliceBomb {
+            session: session(player, true),
+            config: paused_config(),
+            catalogue: catalogue(),
+            authority: signer(player),
+            session_token: None,
+        };
+        assert_paused(fruitninja::slice_bomb(
+            Context::new(&crate::ID, &mut accounts, &[], SliceBombBumps::default()),
+            0,
+        ));
+    }
+
+    #[test]
+    fn activate_powerup_is_paused() {
+        let player = Pubkey::new_unique();
+        let mut accounts = ActivatePowerup {
+            session: session(player, true),
+            config: paused_config(),
+            authority: signer(player),
+            session_token: None,
+        };
+        assert_paused(fruitninja::activate_powerup(
+            Context::new(&crate::ID, &mut accounts, &[], ActivatePowerupBumps::default()),
+            PowerupKind::DoublePoints,
+        ));
+    }
+
+    #[test]
+    fn delegate_session_is_paused() {
+        let player = Pubkey::new_unique();
+        let mut accounts = DelegateSession {
+            payer: signer(player),
+            buffer_session: unchecked().clone(),
+            delegation_record_session: unchecked().clone(),
+            delegation_metadata_session: unchecked().clone(),
+            session: session(player, true),
+            config: paused_config(),
+            owner_program: unchecked().clone(),
+            delegation_program: unchecked().clone(),
+            system_program: system(),
+        };
+        assert_paused(fruitninja::delegate_session(
+            Context::new(&crate::ID, &mut accounts, &[], DelegateSessionBumps::default()),
+            Pubkey::new_unique(),
+        ));
+    }
+
+    #[test]
+    fn delegate_session_with_profile_is_paused() {
+        let player = Pubkey::new_unique();
+        let mut accounts = DelegateSessionWithProfile {
+            payer: signer(player),
+            buffer_session: unchecked().clone(),
+            delegation_record_session: unchecked().clone(),
+            delegation_metadata_session: unchecked().clone(),
+            session: session(player, true),
+            buffer_player_profile: unchecked().clone(),
+            delegation_record_player_profile: unchecked().clone(),
+            delegation_metadata_player_profile: unchecked().clone(),
+            player_profile: profile(player),
+            config: paused_config(),
+            owner_program: unchecked().clone(),
+            delegation_program: unchecked().clone(),
+            system_program: system(),
+        };
+        assert_paused(fruitninja::delegate_session_with_profile(
+            Context::new(&crate::ID, &mut accounts, &[], DelegateSessionWithProfileBumps::default()),
+            Pubkey::new_unique(),
+        ));
+    }
+}
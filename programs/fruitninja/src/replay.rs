@@ -0,0 +1,179 @@
+//! Replay-hash chain for post-hoc score verification.
+//!
+//! Every scoring move of a game is folded into a running digest kept on the
+//! session. The chain starts from the game's RNG seed (published in
+//! `SessionStarted`), and its head is published in `SessionCheckpoint` and
+//! `GameOver`. Off-chain, `verify` rebuilds the score and the digest from the
+//! move events, which proves a leaderboard entry matches its move history.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+/// What a replay step records.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayAction {
+    /// A fruit was sliced (`FruitSliced`, or each fruit of `FruitsSliced`)
+    Slice,
+    /// A fruit fell off screen (`FruitMissed`)
+    Miss,
+    /// A bomb was sliced (`BombHit`); `points` were taken off the score
+    Bomb,
+    /// Multi-slice bonus of a swipe (`FruitsSliced`); `fruit_id` holds the
+    /// number of fruits in the swipe
+    Bonus,
+}
+
+/// One move as reported by the game events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReplayStep {
+    pub action: ReplayAction,
+    pub wave_number: u32,
+    pub fruit_id: u8,
+    pub timestamp: i64,
+    pub points: u64,
+}
+
+impl ReplayStep {
+    /// Score after this step, starting from `score`
+    pub fn apply(&self, score: u64) -> u64 {
+        match self.action {
+            ReplayAction::Slice | ReplayAction::Bonus => score.saturating_add(self.points),
+            ReplayAction::Bomb => score.saturating_sub(self.points),
+            ReplayAction::Miss => score,
+        }
+    }
+}
+
+/// Why a replay does not match the claimed result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayMismatch {
+    Score { expected: u64, replayed: u64 },
+    Digest,
+}
+
+/// Fold one step into the running digest.
+pub fn fold(digest: &[u8; 32], step: &ReplayStep) -> [u8; 32] {
+    hashv(&[
+        digest,
+        &[step.action as u8],
+        &step.wave_number.to_le_bytes(),
+        &[step.fruit_id],
+        &step.timestamp.to_le_bytes(),
+        &step.points.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Replay `steps` from the game's seed, returning the final score and digest.
+pub fn replay(seed: &[u8; 32], steps: &[ReplayStep]) -> (u64, [u8; 32]) {
+    steps
+        .iter()
+        .fold((0, *seed), |(score, digest), step| (step.apply(score), fold(&digest, step)))
+}
+
+/// Check a claimed final score and digest (from `GameOver`) against the moves.
+pub fn verify(
+    seed: &[u8; 32],
+    steps: &[ReplayStep],
+    final_score: u64,
+    digest: &[u8; 32],
+) -> core::result::Result<(), ReplayMismatch> {
+    let (replayed, replayed_digest) = replay(seed, steps);
+    if replayed != final_score {
+        return Err(ReplayMismatch::Score {
+            expected: final_score,
+            replayed,
+        });
+    }
+    if replayed_digest != *digest {
+        return Err(ReplayMismatch::Digest);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: [u8; 32] = [7u8; 32];
+
+    fn step(action: ReplayAction, fruit_id: u8, timestamp: i64, points: u64) -> ReplayStep {
+        ReplayStep {
+            action,
+            wave_number: 1,
+            fruit_id,
+            timestamp,
+            points,
+        }
+    }
+
+    fn game() -> Vec<ReplayStep> {
+        vec![
+            step(ReplayAction::Slice, 0, 100, 10),
+            step(ReplayAction::Slice, 1, 100, 11),
+            step(ReplayAction::Bonus, 2, 100, 5),
+            step(ReplayAction::Miss, 2, 101, 0),
+            step(ReplayAction::Bomb, 3, 102, 8),
+        ]
+    }
+
+    // Fold the steps one by one, the way the program does on-chain
+    fn on_chain(steps: &[ReplayStep]) -> (u64, [u8; 32]) {
+        let mut score = 0;
+        let mut digest = SEED;
+        for step in steps {
+            score = step.apply(score);
+            digest = fold(&digest, step);
+        }
+        (score, digest)
+    }
+
+    #[test]
+    fn replay_matches_on_chain_chain() {
+        let (score, digest) = on_chain(&game());
+        assert_eq!(score, 18);
+        assert_eq!(replay(&SEED, &game()), (score, digest));
+        assert_eq!(verify(&SEED, &game(), score, &digest), Ok(()));
+    }
+
+    #[test]
+    fn empty_game_is_the_seed() {
+        assert_eq!(replay(&SEED, &[]), (0, SEED));
+    }
+
+    #[test]
+    fn inflated_score_is_rejected() {
+        let (_, digest) = on_chain(&game());
+        assert_eq!(
+            verify(&SEED, &game(), 1_000, &digest),
+            Err(ReplayMismatch::Score { expected: 1_000, replayed: 18 })
+        );
+    }
+
+    #[test]
+    fn tampered_moves_break_the_digest() {
+        let (score, digest) = on_chain(&game());
+
+        // Same score, different timing
+        let mut moved = game();
+        moved[3].timestamp = 150;
+        assert_eq!(verify(&SEED, &moved, score, &digest), Err(ReplayMismatch::Digest));
+
+        // Same score, reordered moves
+        let mut reordered = game();
+        reordered.swap(0, 1);
+        assert_eq!(verify(&SEED, &reordered, score, &digest), Err(ReplayMismatch::Digest));
+    }
+
+    #[test]
+    fn chain_is_bound_to_the_game_seed() {
+        let (score, digest) = on_chain(&game());
+        assert_eq!(verify(&[8u8; 32], &game(), score, &digest), Err(ReplayMismatch::Digest));
+    }
+
+    #[test]
+    fn bomb_penalty_saturates_like_the_program() {
+        let steps = [step(ReplayAction::Slice, 0, 1, 3), step(ReplayAction::Bomb, 1, 2, 50)];
+        assert_eq!(replay(&SEED, &steps).0, 0);
+    }
+}
@@ -0,0 +1,257 @@
+//! Pure scoring rules.
+//!
+//! One function turns (curve, base multiplier, fruit points, combo) into the
+//! points a slice earns. Both `slice_fruit` and `calculate_slice_points` go
+//! through it, so the preview can never disagree with the real score.
+//!
+//! Multipliers are expressed in tenths: 10 means 1.0x.
+
+use anchor_lang::prelude::*;
+
+pub const MAX_SCORING_TIERS: usize = 8;
+
+// Fixed-point scale used by the exponential curve
+const FP: u128 = 1_000_000_000;
+const BPS: u128 = 10_000;
+// Keeps fixed-point products comfortably inside u128
+const FP_LIMIT: u128 = 1 << 63;
+
+/// How the combo turns into a points multiplier, on top of
+/// `GameConfig.combo_multiplier_base`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ScoringCurve {
+    /// `base + combo * step`
+    Linear { step: u64 },
+    /// `base + bonus` of the highest tier whose `min_combo` has been reached
+    Stepped { tiers: Vec<ScoringTier> },
+    /// `base * (1 + growth_bps / 10_000) ^ combo`, never above `cap`
+    CappedExponential { growth_bps: u16, cap: u64 },
+}
+
+/// A combo threshold of a stepped curve.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScoringTier {
+    pub min_combo: u32,
+    pub bonus: u64,
+}
+
+impl Default for ScoringCurve {
+    /// The original formula: `points * (base + combo) / 10`.
+    fn default() -> Self {
+        ScoringCurve::Linear { step: 1 }
+    }
+}
+
+impl ScoringCurve {
+    /// Largest serialized size (the stepped variant with every tier used).
+    pub const MAX_SIZE: usize = 1 + 4 + MAX_SCORING_TIERS * (4 + 8);
+
+    /// Tiers must be non-empty, bounded and strictly ascending; an
+    /// exponential curve needs a non-zero cap.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ScoringCurve::Linear { .. } => true,
+            ScoringCurve::Stepped { tiers } => {
+                !tiers.is_empty()
+                    && tiers.len() <= MAX_SCORING_TIERS
+                    && tiers.windows(2).all(|w| w[0].min_combo < w[1].min_combo)
+            }
+            ScoringCurve::CappedExponential { cap, .. } => *cap > 0,
+        }
+    }
+
+    /// Multiplier (in tenths) for `combo`.
+    pub fn multiplier(&self, base: u64, combo: u32) -> u64 {
+        match self {
+            ScoringCurve::Linear { step } => base.saturating_add(step.saturating_mul(combo as u64)),
+            ScoringCurve::Stepped { tiers } => {
+                let bonus = tiers
+                    .iter()
+                    .take_while(|tier| tier.min_combo <= combo)
+                    .last()
+                    .map_or(0, |tier| tier.bonus);
+                base.saturating_add(bonus)
+            }
+            ScoringCurve::CappedExponential { growth_bps, cap } => {
+                if base == 0 {
+                    return 0;
+                }
+                // Stop growing once base * factor would pass the cap
+                let limit = ((*cap as u128) * FP / base as u128 + 1).min(FP_LIMIT);
+                let growth = FP * (BPS + *growth_bps as u128) / BPS;
+                let factor = fp_pow(growth.min(FP_LIMIT), combo, limit);
+                (base as u128 * factor / FP).min(*cap as u128) as u64
+            }
+        }
+    }
+}
+
+/// Points earned by slicing a fruit worth `base_points` at `combo`, scaled
+/// by any power-up multiplier in effect (1 when none is).
+pub fn slice_points(
+    curve: &ScoringCurve,
+    base_multiplier: u64,
+    base_points: u64,
+    combo: u32,
+    powerup_multiplier: u64,
+) -> u64 {
+    base_points
+        .saturating_mul(curve.multiplier(base_multiplier, combo))
+        .saturating_div(10)
+        .saturating_mul(powerup_multiplier)
+}
+
+// Fixed-point `base ^ exp` by squaring, saturating at `limit`
+fn fp_pow(mut base: u128, mut exp: u32, limit: u128) -> u128 {
+    let mut acc = FP;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = (acc * base / FP).min(limit);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = (base * base / FP).min(limit);
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_curve_matches_original_formula() {
+        let curve = ScoringCurve::default();
+        for combo in [0u32, 1, 5, 40] {
+            let legacy = 20 * (10 + combo as u64) / 10;
+            assert_eq!(slice_points(&curve, 10, 20, combo, 1), legacy);
+        }
+    }
+
+    #[test]
+    fn linear_step_scales_combo() {
+        let curve = ScoringCurve::Linear { step: 5 };
+        assert_eq!(curve.multiplier(10, 0), 10);
+        assert_eq!(curve.multiplier(10, 4), 30);
+        assert_eq!(slice_points(&curve, 10, 10, 4, 1), 30);
+    }
+
+    #[test]
+    fn combos_beyond_u8_keep_scoring() {
+        let curve = ScoringCurve::default();
+        assert!(slice_points(&curve, 10, 10, 1_000, 1) > slice_points(&curve, 10, 10, 255, 1));
+    }
+
+    #[test]
+    fn stepped_uses_highest_reached_tier() {
+        let curve = ScoringCurve::Stepped {
+            tiers: vec![
+                ScoringTier { min_combo: 5, bonus: 5 },
+                ScoringTier { min_combo: 10, bonus: 15 },
+                ScoringTier { min_combo: 25, bonus: 40 },
+            ],
+        };
+        assert_eq!(curve.multiplier(10, 0), 10);
+        assert_eq!(curve.multiplier(10, 5), 15);
+        assert_eq!(curve.multiplier(10, 24), 25);
+        assert_eq!(curve.multiplier(10, 1_000), 50);
+    }
+
+    #[test]
+    fn exponential_grows_then_caps() {
+        let curve = ScoringCurve::CappedExponential { growth_bps: 1_000, cap: 50 };
+        assert_eq!(curve.multiplier(10, 0), 10);
+        assert_eq!(curve.multiplier(10, 1), 11);
+        assert_eq!(curve.multiplier(10, 2), 12);
+        assert!(curve.multiplier(10, 10) > curve.multiplier(10, 5));
+        assert_eq!(curve.multiplier(10, 100), 50);
+        assert_eq!(curve.multiplier(10, u32::MAX), 50);
+    }
+
+    #[test]
+    fn exponential_with_huge_growth_does_not_overflow() {
+        let curve = ScoringCurve::CappedExponential { growth_bps: u16::MAX, cap: u64::MAX };
+        assert!(curve.multiplier(u64::MAX / 2, u32::MAX) > 0);
+        assert_eq!(curve.multiplier(0, 10), 0);
+    }
+
+    #[test]
+    fn powerup_multiplier_scales_the_result() {
+        let curve = ScoringCurve::default();
+        let single = slice_points(&curve, 10, 25, 3, 1);
+        assert_eq!(slice_points(&curve, 10, 25, 3, 2), single * 2);
+    }
+
+    #[test]
+    fn validates_curves() {
+        assert!(ScoringCurve::default().is_valid());
+        assert!(!ScoringCurve::Stepped { tiers: vec![] }.is_valid());
+        assert!(!ScoringCurve::Stepped {
+            tiers: vec![
+                ScoringTier { min_combo: 10, bonus: 1 },
+                ScoringTier { min_combo: 10, bonus: 2 },
+            ],
+        }
+        .is_valid());
+        assert!(!ScoringCurve::Stepped {
+            tiers: (0..=MAX_SCORING_TIERS as u32)
+                .map(|min_combo| ScoringTier { min_combo, bonus: 1 })
+                .collect(),
+        }
+        .is_valid());
+        assert!(!ScoringCurve::CappedExponential { growth_bps: 100, cap: 0 }.is_valid());
+    }
+
+    #[test]
+    fn bounds_curve_parameters() {
+        use crate::{validate_curve_bounds, ErrorCode, MAX_CURVE_BONUS, MAX_CURVE_CAP, MAX_CURVE_STEP};
+
+        assert!(validate_curve_bounds(&ScoringCurve::default()).is_ok());
+        assert!(validate_curve_bounds(&ScoringCurve::Linear { step: MAX_CURVE_STEP }).is_ok());
+        assert_eq!(
+            validate_curve_bounds(&ScoringCurve::Linear { step: MAX_CURVE_STEP + 1 }).unwrap_err(),
+            ErrorCode::CurveStepTooLarge.into()
+        );
+        let tiers = vec![ScoringTier { min_combo: 5, bonus: MAX_CURVE_BONUS + 1 }];
+        assert_eq!(
+            validate_curve_bounds(&ScoringCurve::Stepped { tiers }).unwrap_err(),
+            ErrorCode::CurveBonusTooLarge.into()
+        );
+        let curve = ScoringCurve::CappedExponential { growth_bps: 1_000, cap: MAX_CURVE_CAP + 1 };
+        assert_eq!(validate_curve_bounds(&curve).unwrap_err(), ErrorCode::CurveCapTooLarge.into());
+    }
+
+    #[test]
+    fn preview_matches_the_slice() {
+        let config = crate::GameConfig::new(Pubkey::new_unique(), 3, 100, 10, 255);
+        let catalogue = crate::FruitCatalogue {
+            bump: 255,
+            kinds: vec![crate::FruitKind { base_points: 10, rarity_weight: 1, flags: 0 }],
+        };
+        let legacy = crate::migration::LegacyGameSession {
+            player: Pubkey::new_unique(),
+            bump: 255,
+            current_score: 0,
+            combo: 0,
+            lives: 3,
+            is_active: true,
+            started_at: 1_000,
+            ended_at: None,
+            fruits_sliced: 0,
+            max_combo: 0,
+        };
+        let mut session = crate::migration::upgrade_session(legacy, [0; 32]);
+        session.wave_len = 4;
+
+        // Three quick slices build a combo, the fourth comes after it decayed
+        let window_s = (config.combo_window_ms / 1000) as i64;
+        for (fruit_id, now) in [(0, 1_001), (1, 1_001), (2, 1_002), (3, 1_003 + window_s)] {
+            let preview = crate::slice_points(&config, &session, 10, now);
+            let (_, earned) = crate::apply_slice(&mut session, &config, &catalogue, fruit_id, now).unwrap();
+            assert_eq!(preview, earned);
+        }
+        assert_eq!(session.max_combo, 3);
+        assert_eq!(session.combo, 1);
+    }
+}
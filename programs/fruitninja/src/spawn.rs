@@ -0,0 +1,143 @@
+//! Deterministic fruit wave generation.
+//!
+//! The program, not the client, decides which fruits are in play. Each game
+//! is seeded from a recent slot hash when it starts. Every wave is derived
+//! from the previous seed, the session's replay hash and the slot the wave
+//! was spawned in, so the fruit sequence can be recomputed off-chain from the
+//! starting seed, the move events and the `WaveSpawned` slots.
+//!
+//! This is not a secure RNG. Slot hashes and the current slot are public
+//! before a transaction lands, so a client can estimate upcoming waves and
+//! time its `spawn_wave` calls. Mixing in the replay hash and the landing slot
+//! makes that costly, since every move changes the outcome and the landing
+//! slot is not fully under the client's control, but it does not prevent it.
+
+use anchor_lang::solana_program::hash::hashv;
+
+pub const MIN_WAVE_SIZE: usize = 3;
+pub const MAX_WAVE_SIZE: usize = 8;
+
+/// A freshly spawned wave: `kinds[..len]` are the fruit kind ids in play.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wave {
+    pub kinds: [u8; MAX_WAVE_SIZE],
+    pub len: u8,
+}
+
+/// Seed for a new game, mixed from a recent slot hash and the game identity
+/// so two games never share a fruit sequence.
+pub fn game_seed(slot_hash: &[u8; 32], player: &[u8], game_index: u64) -> [u8; 32] {
+    hashv(&[slot_hash, player, &game_index.to_le_bytes()]).to_bytes()
+}
+
+/// Seed of the next wave, chained from the current one and mixed with the
+/// moves played so far (`replay_hash`) and the slot the wave spawns in.
+pub fn next_wave_seed(seed: &[u8; 32], wave_number: u32, replay_hash: &[u8; 32], slot: u64) -> [u8; 32] {
+    hashv(&[seed, &wave_number.to_le_bytes(), replay_hash, &slot.to_le_bytes()]).to_bytes()
+}
+
+/// Expand a wave seed into fruit kinds, drawn in proportion to `weights`
+/// (indexed by kind id). Returns `None` if no kind can spawn.
+pub fn generate_wave(seed: &[u8; 32], weights: &[u16]) -> Option<Wave> {
+    let total: u64 = weights.iter().map(|&w| w as u64).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut state = u64::from_le_bytes(seed[..8].try_into().unwrap());
+    let span = (MAX_WAVE_SIZE - MIN_WAVE_SIZE + 1) as u64;
+    let len = MIN_WAVE_SIZE + (splitmix64(&mut state) % span) as usize;
+
+    let mut kinds = [0u8; MAX_WAVE_SIZE];
+    for kind in kinds.iter_mut().take(len) {
+        *kind = pick_weighted(weights, splitmix64(&mut state) % total);
+    }
+
+    Some(Wave {
+        kinds,
+        len: len as u8,
+    })
+}
+
+// Map a roll in [0, total weight) to the kind whose weight range contains it
+fn pick_weighted(weights: &[u16], mut roll: u64) -> u8 {
+    for (id, &weight) in weights.iter().enumerate() {
+        if roll < weight as u64 {
+            return id as u8;
+        }
+        roll -= weight as u64;
+    }
+    unreachable!("roll is always below the total weight")
+}
+
+// SplitMix64: tiny, well-distributed and identical on every platform
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEIGHTS: [u16; 5] = [30, 25, 20, 15, 10];
+
+    #[test]
+    fn waves_are_deterministic() {
+        let seed = game_seed(&[7u8; 32], &[1u8; 32], 0);
+        assert_eq!(generate_wave(&seed, &WEIGHTS), generate_wave(&seed, &WEIGHTS));
+    }
+
+    #[test]
+    fn waves_stay_in_bounds() {
+        let mut seed = game_seed(&[3u8; 32], &[9u8; 32], 4);
+        for wave_number in 0..256 {
+            seed = next_wave_seed(&seed, wave_number, &[0u8; 32], wave_number as u64);
+            let wave = generate_wave(&seed, &WEIGHTS).unwrap();
+            let len = wave.len as usize;
+            assert!((MIN_WAVE_SIZE..=MAX_WAVE_SIZE).contains(&len));
+            assert!(wave.kinds[..len]
+                .iter()
+                .all(|&kind| (kind as usize) < WEIGHTS.len()));
+            assert!(wave.kinds[len..].iter().all(|&kind| kind == 0));
+        }
+    }
+
+    #[test]
+    fn zero_weight_kinds_never_spawn() {
+        let weights = [0, 5, 0, 5];
+        let mut seed = game_seed(&[1u8; 32], &[4u8; 32], 0);
+        for wave_number in 0..128 {
+            seed = next_wave_seed(&seed, wave_number, &[0u8; 32], wave_number as u64);
+            let wave = generate_wave(&seed, &weights).unwrap();
+            assert!(wave.kinds[..wave.len as usize]
+                .iter()
+                .all(|&kind| kind == 1 || kind == 3));
+        }
+    }
+
+    #[test]
+    fn empty_catalogue_spawns_nothing() {
+        let seed = game_seed(&[1u8; 32], &[4u8; 32], 0);
+        assert_eq!(generate_wave(&seed, &[]), None);
+        assert_eq!(generate_wave(&seed, &[0, 0]), None);
+    }
+
+    #[test]
+    fn different_games_get_different_seeds() {
+        let slot_hash = [5u8; 32];
+        let player = [2u8; 32];
+        assert_ne!(game_seed(&slot_hash, &player, 0), game_seed(&slot_hash, &player, 1));
+    }
+
+    #[test]
+    fn wave_seed_depends_on_moves_and_slot() {
+        let seed = game_seed(&[5u8; 32], &[2u8; 32], 0);
+        let base = next_wave_seed(&seed, 1, &[0u8; 32], 100);
+        assert_ne!(base, next_wave_seed(&seed, 1, &[1u8; 32], 100));
+        assert_ne!(base, next_wave_seed(&seed, 1, &[0u8; 32], 101));
+    }
+}
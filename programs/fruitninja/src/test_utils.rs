@@ -0,0 +1,74 @@
+//! Account fixtures for calling instruction handlers directly in tests.
+//!
+//! Handlers called this way skip the account constraints; the fixtures only
+//! have to deserialize. Everything is leaked to get `'static` accounts.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::migration::{upgrade_session, LegacyGameSession};
+use crate::{FruitCatalogue, GameSession, PlayerProfile, PROFILE_RESERVED};
+
+pub fn account_info(key: Pubkey, is_signer: bool, owner: Pubkey, data: Vec<u8>, executable: bool) -> &'static AccountInfo<'static> {
+    Box::leak(Box::new(AccountInfo::new(
+        Box::leak(Box::new(key)),
+        is_signer,
+        true,
+        Box::leak(Box::new(0u64)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        executable,
+        0,
+    )))
+}
+
+pub fn unchecked() -> &'static AccountInfo<'static> {
+    account_info(Pubkey::new_unique(), false, system_program::ID, vec![], false)
+}
+
+pub fn program_account<T: AccountSerialize + AccountDeserialize + Owner + Clone>(value: &T) -> Account<'static, T> {
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    Account::try_from(account_info(Pubkey::new_unique(), false, crate::ID, data, false)).unwrap()
+}
+
+pub fn signer(key: Pubkey) -> Signer<'static> {
+    Signer::try_from(account_info(key, true, system_program::ID, vec![], false)).unwrap()
+}
+
+pub fn system() -> Program<'static, System> {
+    Program::try_from(account_info(system_program::ID, false, Pubkey::default(), vec![], true)).unwrap()
+}
+
+pub fn session(player: Pubkey, is_active: bool) -> Account<'static, GameSession> {
+    let legacy = LegacyGameSession {
+        player,
+        bump: 255,
+        current_score: 0,
+        combo: 0,
+        lives: 3,
+        is_active,
+        started_at: 0,
+        ended_at: None,
+        fruits_sliced: 0,
+        max_combo: 0,
+    };
+    program_account(&upgrade_session(legacy, [0; 32]))
+}
+
+pub fn profile(owner: Pubkey) -> Account<'static, PlayerProfile> {
+    program_account(&PlayerProfile {
+        version: PlayerProfile::VERSION,
+        owner,
+        username: None,
+        high_score: 0,
+        total_games: 0,
+        total_fruits_sliced: 0,
+        bump: 255,
+        reserved: [0; PROFILE_RESERVED],
+    })
+}
+
+pub fn catalogue() -> Account<'static, FruitCatalogue> {
+    program_account(&FruitCatalogue { bump: 255, kinds: vec![] })
+}